@@ -0,0 +1,266 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::base58decoder::{base58check_decode, Base58Error};
+use crate::bech32::{self, Bech32Error};
+use crate::network::Network;
+
+#[derive(Debug, PartialEq)]
+pub enum AddressError {
+    InvalidBase58(Base58Error),
+    InvalidBech32(Bech32Error),
+    InvalidLength(usize),
+    UnknownVersion(u8),
+    UnknownHrp(String),
+}
+
+impl From<Base58Error> for AddressError {
+    fn from(err: Base58Error) -> Self {
+        AddressError::InvalidBase58(err)
+    }
+}
+
+impl From<Bech32Error> for AddressError {
+    fn from(err: Bech32Error) -> Self {
+        AddressError::InvalidBech32(err)
+    }
+}
+
+/// The kind of output script an address pays to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+    /// A segwit output with a witness version or program not yet assigned a meaning.
+    WitnessUnknown(u8),
+}
+
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressType::P2pkh => write!(f, "p2pkh"),
+            AddressType::P2sh => write!(f, "p2sh"),
+            AddressType::P2wpkh => write!(f, "p2wpkh"),
+            AddressType::P2wsh => write!(f, "p2wsh"),
+            AddressType::P2tr => write!(f, "p2tr"),
+            AddressType::WitnessUnknown(version) => write!(f, "witness_v{}", version),
+        }
+    }
+}
+
+/// A parsed Bitcoin address
+///
+/// Testnet and signet share all their prefixes, and regtest shares the base58 ones, so such
+/// addresses are reported as `Network::Testnet`.
+#[derive(Debug, PartialEq)]
+pub struct Address {
+    pub network: Network,
+    pub address_type: AddressType,
+    /// The witness version, for segwit addresses.
+    pub witness_version: Option<u8>,
+    /// The hash for base58 addresses, or the witness program for segwit addresses.
+    pub payload: Vec<u8>,
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    /// Parses and validates a base58check or bech32/bech32m encoded address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address as a string slice.
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        let lowercase = address.to_lowercase();
+
+        let is_segwit = ["bc1", "tb1", "bcrt1"]
+            .iter()
+            .any(|hrp| lowercase.starts_with(hrp));
+
+        match is_segwit {
+            true => Address::from_bech32(address),
+            false => Address::from_base58(address),
+        }
+    }
+}
+
+impl Address {
+    fn from_base58(address: &str) -> Result<Self, AddressError> {
+        let decoded = base58check_decode(address)?;
+
+        if decoded.len() != 21 {
+            return Err(AddressError::InvalidLength(decoded.len()));
+        }
+
+        let (network, address_type) = match decoded[0] {
+            0x00 => (Network::Mainnet, AddressType::P2pkh),
+            0x05 => (Network::Mainnet, AddressType::P2sh),
+            0x6f => (Network::Testnet, AddressType::P2pkh),
+            0xc4 => (Network::Testnet, AddressType::P2sh),
+            version => return Err(AddressError::UnknownVersion(version)),
+        };
+
+        Ok(Address {
+            network,
+            address_type,
+            witness_version: None,
+            payload: decoded[1..].to_vec(),
+        })
+    }
+
+    fn from_bech32(address: &str) -> Result<Self, AddressError> {
+        let (hrp, witness_version, program) = bech32::decode_segwit_address(address)?;
+
+        let network = match hrp.as_str() {
+            "bc" => Network::Mainnet,
+            "tb" => Network::Testnet,
+            "bcrt" => Network::Regtest,
+            _ => return Err(AddressError::UnknownHrp(hrp)),
+        };
+
+        let address_type = match (witness_version, program.len()) {
+            (0, 20) => AddressType::P2wpkh,
+            (0, 32) => AddressType::P2wsh,
+            (1, 32) => AddressType::P2tr,
+            (version, _) => AddressType::WitnessUnknown(version),
+        };
+
+        Ok(Address {
+            network,
+            address_type,
+            witness_version: Some(witness_version),
+            payload: program,
+        })
+    }
+
+    /// Returns the scriptPubKey locking the outputs paid to this address.
+    pub fn script_pubkey(&self) -> Vec<u8> {
+        let mut script = match (self.address_type, self.witness_version) {
+            (AddressType::P2pkh, _) => vec![0x76, 0xa9, 0x14],
+            (AddressType::P2sh, _) => vec![0xa9, 0x14],
+            (_, Some(0)) => vec![0x00, self.payload.len() as u8],
+            (_, Some(version)) => vec![0x50 + version, self.payload.len() as u8],
+            (_, None) => unreachable!("segwit addresses always have a witness version"),
+        };
+
+        script.extend_from_slice(&self.payload);
+
+        match self.address_type {
+            AddressType::P2pkh => script.extend_from_slice(&[0x88, 0xac]),
+            AddressType::P2sh => script.push(0x87),
+            _ => (),
+        }
+
+        script
+    }
+}
+
+#[cfg(test)]
+mod address_tests {
+    use super::*;
+    use crate::key::Key;
+    use crate::key::{ADDRESS_FROM_COMPRESSED, P2SH_P2WPKH_ADDRESS, P2WPKH_ADDRESS, WIF};
+
+    fn script_pubkey(address: &str) -> String {
+        hex::encode(Address::from_str(address).unwrap().script_pubkey())
+    }
+
+    #[test]
+    fn should_parse_p2pkh_address() {
+        let address = Address::from_str(ADDRESS_FROM_COMPRESSED).unwrap();
+
+        assert_eq!(address.network, Network::Mainnet);
+        assert_eq!(address.address_type, AddressType::P2pkh);
+        assert_eq!(address.witness_version, None);
+        assert_eq!(
+            address.payload,
+            hex::decode("bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap()
+        );
+        assert_eq!(
+            script_pubkey(ADDRESS_FROM_COMPRESSED),
+            "76a914bbc1e42a39d05a4cc61752d6963b7f69d09bb27b88ac",
+        );
+    }
+
+    #[test]
+    fn should_parse_p2sh_address() {
+        let address = Address::from_str(P2SH_P2WPKH_ADDRESS).unwrap();
+
+        assert_eq!(address.network, Network::Mainnet);
+        assert_eq!(address.address_type, AddressType::P2sh);
+        assert_eq!(script_pubkey("2N7XQ9yUwZxXP7WgkFMtbz9jDs2Kr2njYRy")[..4], *"a914");
+        assert_eq!(
+            Address::from_str("2N7XQ9yUwZxXP7WgkFMtbz9jDs2Kr2njYRy").unwrap().network,
+            Network::Testnet,
+        );
+    }
+
+    #[test]
+    fn should_parse_segwit_addresses() {
+        let address = Address::from_str(P2WPKH_ADDRESS).unwrap();
+
+        assert_eq!(address.address_type, AddressType::P2wpkh);
+        assert_eq!(address.witness_version, Some(0));
+        assert_eq!(
+            script_pubkey(P2WPKH_ADDRESS),
+            "0014bbc1e42a39d05a4cc61752d6963b7f69d09bb27b",
+        );
+
+        assert_eq!(
+            Address::from_str("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7")
+                .unwrap()
+                .address_type,
+            AddressType::P2wsh,
+        );
+
+        assert_eq!(
+            script_pubkey("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"),
+            "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        );
+
+        let address = Address::from_str("BC1SW50QGDZ25J").unwrap();
+        assert_eq!(address.address_type, AddressType::WitnessUnknown(16));
+        assert_eq!(hex::encode(address.script_pubkey()), "6002751e");
+    }
+
+    #[test]
+    fn should_parse_regtest_address() {
+        let address = Address::from_str("bcrt1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmnxyz5v").unwrap();
+
+        assert_eq!(address.network, Network::Regtest);
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_checksum() {
+        assert_eq!(
+            Address::from_str("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZz"),
+            Err(AddressError::InvalidBase58(Base58Error::InvalidChecksum)),
+        );
+        assert_eq!(
+            Address::from_str("bc1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmmfxucq"),
+            Err(AddressError::InvalidBech32(Bech32Error::InvalidChecksum)),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_unknown_version() {
+        let mut payload = hex::decode("30bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap();
+        payload.append_checksum();
+
+        assert_eq!(
+            Address::from_str(&bs58::encode(payload).into_string()),
+            Err(AddressError::UnknownVersion(0x30)),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_length() {
+        assert_eq!(
+            Address::from_str(WIF),
+            Err(AddressError::InvalidLength(33)),
+        );
+    }
+}
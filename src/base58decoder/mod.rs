@@ -1,3 +1,7 @@
+use std::fmt;
+
+use crate::key::Key;
+
 /// Represents a decoded base58check string
 ///
 /// The first value is the version;
@@ -5,8 +9,71 @@
 /// The third value is the checksum;
 type Decoded = (String, String, String);
 
+#[derive(Debug, PartialEq)]
+pub enum Base58Error {
+    InvalidCharacter { character: char, index: usize },
+    TooShort(usize),
+    InvalidChecksum,
+    Decode(bs58::decode::Error),
+}
+
+/// The kind of data a base58check string most likely carries, guessed from its version bytes
+/// and length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadType {
+    Wif,
+    WifCompressed,
+    P2pkh,
+    P2sh,
+    Xprv,
+    Xpub,
+}
+
+impl fmt::Display for PayloadType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PayloadType::Wif => "WIF private key",
+            PayloadType::WifCompressed => "WIF-compressed private key",
+            PayloadType::P2pkh => "P2PKH address",
+            PayloadType::P2sh => "P2SH address",
+            PayloadType::Xprv => "extended private key",
+            PayloadType::Xpub => "extended public key",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Decodes a base58 string slice into bytes, reporting the offending character if any
+fn decode_bytes(input: &str) -> Result<Vec<u8>, Base58Error> {
+    bs58::decode(input).into_vec().map_err(|err| match err {
+        bs58::decode::Error::InvalidCharacter { character, index } => {
+            Base58Error::InvalidCharacter { character, index }
+        }
+        bs58::decode::Error::NonAsciiCharacter { index } => Base58Error::InvalidCharacter {
+            character: input[index..].chars().next().unwrap_or_default(),
+            index,
+        },
+        err => Base58Error::Decode(err),
+    })
+}
+
+/// Verifies that the last four bytes of `decoded` are the checksum of the rest
+fn verify_checksum(decoded: &[u8]) -> Result<(), Base58Error> {
+    let mut expected = decoded[..decoded.len() - 4].to_vec();
+    expected.append_checksum();
+
+    if expected != decoded {
+        return Err(Base58Error::InvalidChecksum);
+    }
+
+    Ok(())
+}
+
 /// Decodes a base58check encoded string slice
 ///
+/// The checksum is split but not verified, see `base58check_decode` for that.
+///
 /// # Arguments
 ///
 /// * `input`: A base58check enconded string slice
@@ -14,8 +81,12 @@ type Decoded = (String, String, String);
 /// # Return
 ///
 /// * A `Decoded` type
-pub fn base58decode(input: &str) -> Result<Decoded, bs58::decode::Error> {
-    let decoded = bs58::decode(input).into_vec()?;
+pub fn base58decode(input: &str) -> Result<Decoded, Base58Error> {
+    let decoded = decode_bytes(input)?;
+
+    if decoded.len() < 5 {
+        return Err(Base58Error::TooShort(decoded.len()));
+    }
 
     let version = hex::encode(vec![decoded[0]]);
     let payload = hex::encode(&decoded[1..decoded.len() - 4]);
@@ -24,9 +95,52 @@ pub fn base58decode(input: &str) -> Result<Decoded, bs58::decode::Error> {
     Ok((version, payload, checksum))
 }
 
+/// Decodes a base58check encoded string slice, verifying its checksum
+///
+/// # Arguments
+///
+/// * `input`: A base58check enconded string slice
+///
+/// # Return
+///
+/// * The version bytes followed by the payload, without the checksum
+pub fn base58check_decode(input: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut decoded = decode_bytes(input)?;
+
+    if decoded.len() < 5 {
+        return Err(Base58Error::TooShort(decoded.len()));
+    }
+
+    verify_checksum(&decoded)?;
+
+    decoded.truncate(decoded.len() - 4);
+
+    Ok(decoded)
+}
+
+/// Guesses what a base58check payload is from its version bytes and length
+///
+/// # Arguments
+///
+/// * `data`: The version bytes followed by the payload, without the checksum
+pub fn guess_payload_type(data: &[u8]) -> Option<PayloadType> {
+    match (data.len(), data.first()?) {
+        (33, 0x80 | 0xef) => Some(PayloadType::Wif),
+        (34, 0x80 | 0xef) if data[33] == 0x01 => Some(PayloadType::WifCompressed),
+        (21, 0x00 | 0x6f) => Some(PayloadType::P2pkh),
+        (21, 0x05 | 0xc4) => Some(PayloadType::P2sh),
+        (78, _) => match data[0..4] {
+            [0x04, 0x88, 0xad, 0xe4] | [0x04, 0x35, 0x83, 0x94] => Some(PayloadType::Xprv),
+            [0x04, 0x88, 0xb2, 0x1e] | [0x04, 0x35, 0x87, 0xcf] => Some(PayloadType::Xpub),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod base58decoder_tests {
-    use super::base58decode;
+    use super::*;
 
     #[test]
     fn test_with_wif_private_key_format() {
@@ -58,7 +172,60 @@ mod base58decoder_tests {
     fn should_throw_error_if_invalid_base58_char() {
         assert_eq!(
             base58decode("KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtl"),
-            Err(bs58::decode::Error::InvalidCharacter { character: 'l', index: 51 }),
+            Err(Base58Error::InvalidCharacter { character: 'l', index: 51 }),
         )
     }
+
+    #[test]
+    fn should_throw_error_if_non_ascii_char() {
+        assert_eq!(
+            base58check_decode("1é"),
+            Err(Base58Error::InvalidCharacter { character: 'é', index: 1 }),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_input_is_too_short() {
+        assert_eq!(base58decode(""), Err(Base58Error::TooShort(0)));
+        assert_eq!(base58decode("1111"), Err(Base58Error::TooShort(4)));
+        assert_eq!(base58check_decode("2g"), Err(Base58Error::TooShort(1)));
+    }
+
+    #[test]
+    fn should_verify_checksum() {
+        assert!(base58check_decode("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy").is_ok());
+        assert_eq!(
+            base58check_decode("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZz"),
+            Err(Base58Error::InvalidChecksum),
+        );
+    }
+
+    #[test]
+    fn should_strip_checksum() {
+        assert_eq!(
+            base58check_decode("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy").unwrap(),
+            hex::decode("00bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap(),
+        );
+    }
+
+    fn guess(input: &str) -> Option<PayloadType> {
+        guess_payload_type(&base58check_decode(input).unwrap())
+    }
+
+    #[test]
+    fn should_guess_payload_type() {
+        assert_eq!(guess("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jcn"), Some(PayloadType::Wif));
+        assert_eq!(guess("KxFC1jmwwCoACiCAWZ3eXa96mBM6tb3TYzGmf6YwgdGWZgawvrtJ"), Some(PayloadType::WifCompressed));
+        assert_eq!(guess("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy"), Some(PayloadType::P2pkh));
+        assert_eq!(guess("3FyC6EYuxW22uj4CaEGjNCjxeg7gHyFeVv"), Some(PayloadType::P2sh));
+        assert_eq!(
+            guess("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"),
+            Some(PayloadType::Xprv),
+        );
+        assert_eq!(
+            guess("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"),
+            Some(PayloadType::Xpub),
+        );
+        assert_eq!(guess_payload_type(&[0x00; 10]), None);
+    }
 }
@@ -0,0 +1,64 @@
+use crate::key::Key;
+
+/// Encodes a payload as a base58check string
+///
+/// # Arguments
+///
+/// * `version`: The version bytes, e.g. `[0x80]` for WIF or `[0x04, 0x88, 0xb2, 0x1e]` for xpub
+/// * `payload`: The payload bytes
+///
+/// # Return
+///
+/// * The base58 encoding of version, payload and the first four bytes of their double SHA256
+pub fn base58check_encode(version: &[u8], payload: &[u8]) -> String {
+    let mut data = version.to_vec();
+    data.extend_from_slice(payload);
+    data.append_checksum();
+
+    bs58::encode(data).into_string()
+}
+
+#[cfg(test)]
+mod base58encoder_tests {
+    use super::base58check_encode;
+    use crate::base58decoder::base58check_decode;
+    use crate::key::{ADDRESS_FROM_COMPRESSED, COMPRESSED_WIF, PRIVATE_KEY, WIF};
+
+    #[test]
+    fn test_with_wif_private_key_format() {
+        let key = hex::decode(PRIVATE_KEY).unwrap();
+
+        assert_eq!(base58check_encode(&[0x80], &key), WIF);
+    }
+
+    #[test]
+    fn test_with_wif_compressed_private_key_format() {
+        let mut key = hex::decode(PRIVATE_KEY).unwrap();
+        key.push(0x01);
+
+        assert_eq!(base58check_encode(&[0x80], &key), COMPRESSED_WIF);
+    }
+
+    #[test]
+    fn test_with_p2pkh_address() {
+        let pkh = hex::decode("bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap();
+
+        assert_eq!(base58check_encode(&[0x00], &pkh), ADDRESS_FROM_COMPRESSED);
+    }
+
+    #[test]
+    fn should_roundtrip_multi_byte_versions() {
+        let version = [0x04, 0x88, 0xb2, 0x1e];
+        let payload = [0x42; 74];
+
+        let decoded = base58check_decode(&base58check_encode(&version, &payload)).unwrap();
+
+        assert_eq!(decoded[..4], version);
+        assert_eq!(decoded[4..], payload);
+    }
+
+    #[test]
+    fn should_encode_empty_payload() {
+        assert_eq!(base58check_encode(&[0x00], &[]), "1Wh4bh");
+    }
+}
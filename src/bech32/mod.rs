@@ -0,0 +1,343 @@
+#[derive(Debug, PartialEq)]
+pub enum Bech32Error {
+    MixedCase,
+    InvalidLength,
+    MissingSeparator,
+    InvalidCharacter(char),
+    InvalidChecksum,
+    InvalidPadding,
+    InvalidWitnessVersion(u8),
+    InvalidProgramLength(usize),
+    WrongVariant,
+}
+
+/// The 32 characters used by bech32, indexed by their 5-bit value.
+pub const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// The checksum flavour: bech32 (BIP173) for witness v0, bech32m (BIP350) for v1 and above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    /// The constant XORed into the checksum.
+    fn constant(&self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+
+    /// Returns the variant a segwit address of the given witness version must use.
+    pub fn for_witness_version(witness_version: u8) -> Self {
+        match witness_version {
+            0 => Variant::Bech32,
+            _ => Variant::Bech32m,
+        }
+    }
+}
+
+/// Computes the BCH checksum over a sequence of 5-bit values.
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk: u32 = 1;
+
+    for v in values {
+        let b = chk >> 25;
+        chk = (chk & 0x1ffffff) << 5 ^ *v as u32;
+
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+
+    chk
+}
+
+/// Expands the human readable part into the values fed to the checksum.
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|c| c & 31));
+
+    expanded
+}
+
+/// Returns the six 5-bit values of the checksum for `hrp` and `data`.
+fn create_checksum(hrp: &str, data: &[u8], variant: Variant) -> Vec<u8> {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0; 6]);
+
+    let polymod = polymod(&values) ^ variant.constant();
+
+    (0..6).map(|i| ((polymod >> (5 * (5 - i))) & 31) as u8).collect()
+}
+
+/// Regroups a slice of `from`-bit values into `to`-bit values.
+///
+/// # Arguments
+///
+/// * `data`: The values to regroup
+/// * `from`: Bit width of the input values
+/// * `to`: Bit width of the output values
+/// * `pad`: Whether leftover bits are padded with zeros into a last value
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut converted = Vec::new();
+    let max = (1 << to) - 1;
+
+    for value in data {
+        if (*value as u32) >> from != 0 {
+            return None;
+        }
+
+        acc = (acc << from) | *value as u32;
+        bits += from;
+
+        while bits >= to {
+            bits -= to;
+            converted.push(((acc >> bits) & max) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            converted.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return None;
+    }
+
+    Some(converted)
+}
+
+/// Encodes 5-bit values as a bech32 or bech32m string
+///
+/// # Arguments
+///
+/// * `hrp`: The human readable part, e.g. "bc"
+/// * `data`: The 5-bit values of the data part
+/// * `variant`: Which checksum constant to use
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> String {
+    let mut values = data.to_vec();
+    values.append(&mut create_checksum(hrp, data, variant));
+
+    let encoded: String = values.iter().map(|v| CHARSET[*v as usize] as char).collect();
+
+    format!("{}1{}", hrp, encoded)
+}
+
+/// Encodes a segwit address given its witness version and program
+///
+/// Version 0 programs are bech32 encoded, later versions bech32m encoded.
+///
+/// # Arguments
+///
+/// * `hrp`: The human readable part of the network, e.g. "bc"
+/// * `witness_version`: The witness version, from 0 to 16
+/// * `program`: The witness program, e.g. the hash160 of a public key
+pub fn encode_segwit_address(hrp: &str, witness_version: u8, program: &[u8]) -> String {
+    let mut data = vec![witness_version];
+    data.append(&mut convert_bits(program, 8, 5, true).unwrap());
+
+    encode(hrp, &data, Variant::for_witness_version(witness_version))
+}
+
+/// Decodes a bech32 or bech32m string
+///
+/// # Arguments
+///
+/// * `input`: A bech32 encoded string slice, either all lowercase or all uppercase
+///
+/// # Return
+///
+/// * The lowercase human readable part, the 5-bit values of the data part without checksum,
+///   and the variant whose checksum matched
+pub fn decode(input: &str) -> Result<(String, Vec<u8>, Variant), Bech32Error> {
+    if input.len() > 90 {
+        return Err(Bech32Error::InvalidLength);
+    }
+
+    let has_lower = input.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = input.chars().any(|c| c.is_ascii_uppercase());
+
+    if has_lower && has_upper {
+        return Err(Bech32Error::MixedCase);
+    }
+
+    let input = input.to_lowercase();
+
+    let separator = input.rfind('1').ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, data) = (&input[..separator], &input[separator + 1..]);
+
+    if hrp.is_empty() || data.len() < 6 {
+        return Err(Bech32Error::InvalidLength);
+    }
+
+    if let Some(c) = hrp.chars().find(|c| !(33..=126).contains(&(*c as u32))) {
+        return Err(Bech32Error::InvalidCharacter(c));
+    }
+
+    let values = data
+        .chars()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|x| *x as char == c)
+                .map(|v| v as u8)
+                .ok_or(Bech32Error::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<u8>, Bech32Error>>()?;
+
+    let mut checked = hrp_expand(hrp);
+    checked.extend_from_slice(&values);
+
+    let variant = match polymod(&checked) {
+        c if c == Variant::Bech32.constant() => Variant::Bech32,
+        c if c == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => return Err(Bech32Error::InvalidChecksum),
+    };
+
+    Ok((hrp.to_string(), values[..values.len() - 6].to_vec(), variant))
+}
+
+/// Decodes a segwit address, checking the witness version, program length and checksum variant
+///
+/// # Arguments
+///
+/// * `address`: A bech32 or bech32m encoded segwit address
+///
+/// # Return
+///
+/// * The human readable part, the witness version and the witness program
+pub fn decode_segwit_address(address: &str) -> Result<(String, u8, Vec<u8>), Bech32Error> {
+    let (hrp, data, variant) = decode(address)?;
+
+    let (witness_version, program) = data.split_first().ok_or(Bech32Error::InvalidLength)?;
+    let witness_version = *witness_version;
+
+    if witness_version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion(witness_version));
+    }
+
+    if variant != Variant::for_witness_version(witness_version) {
+        return Err(Bech32Error::WrongVariant);
+    }
+
+    let program = convert_bits(program, 5, 8, false).ok_or(Bech32Error::InvalidPadding)?;
+
+    if program.len() < 2 || program.len() > 40 {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+
+    if witness_version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+
+    Ok((hrp, witness_version, program))
+}
+
+#[cfg(test)]
+mod bech32_tests {
+    use super::*;
+
+    #[test]
+    fn should_encode_bip173_p2wpkh_vector() {
+        let program = hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+
+        assert_eq!(
+            encode_segwit_address("bc", 0, &program),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        );
+        assert_eq!(
+            encode_segwit_address("tb", 0, &program),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+        );
+    }
+
+    #[test]
+    fn should_encode_bip173_p2wsh_vector() {
+        let program =
+            hex::decode("1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262")
+                .unwrap();
+
+        assert_eq!(
+            encode_segwit_address("tb", 0, &program),
+            "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+        );
+    }
+
+    #[test]
+    fn should_encode_empty_data() {
+        assert_eq!(encode("a", &[], Variant::Bech32), "a12uel5l");
+        assert_eq!(encode("a", &[], Variant::Bech32m), "a1lqfn3a");
+    }
+
+    #[test]
+    fn should_encode_bip350_p2tr_vector() {
+        let program =
+            hex::decode("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+
+        assert_eq!(
+            encode_segwit_address("bc", 1, &program),
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+        );
+    }
+
+    #[test]
+    fn should_roundtrip_bits() {
+        let data = vec![0xff, 0x00, 0x42];
+        let five = convert_bits(&data, 8, 5, true).unwrap();
+
+        assert_eq!(convert_bits(&five, 5, 8, false).unwrap(), data);
+    }
+
+    #[test]
+    fn should_decode_valid_segwit_addresses() {
+        let cases = [
+            ("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", "bc", 0, "751e76e8199196d454941c45d1b3a323f1433bd6"),
+            ("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", "tb", 0, "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+            ("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y", "bc", 1, "751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6"),
+            ("BC1SW50QGDZ25J", "bc", 16, "751e"),
+            ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", "bc", 1, "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+        ];
+
+        for (address, hrp, version, program) in cases {
+            assert_eq!(
+                decode_segwit_address(address).unwrap(),
+                (hrp.to_string(), version, hex::decode(program).unwrap()),
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_segwit_addresses() {
+        let cases = [
+            ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd", Bech32Error::WrongVariant),
+            ("BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL", Bech32Error::WrongVariant),
+            ("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh", Bech32Error::WrongVariant),
+            ("bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4", Bech32Error::InvalidCharacter('o')),
+            ("BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R", Bech32Error::InvalidWitnessVersion(17)),
+            ("bc1pw5dgrnzv", Bech32Error::InvalidProgramLength(1)),
+            ("BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P", Bech32Error::InvalidProgramLength(16)),
+            ("tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47", Bech32Error::WrongVariant),
+            ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav", Bech32Error::InvalidProgramLength(41)),
+            ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf", Bech32Error::InvalidPadding),
+            ("tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j", Bech32Error::InvalidPadding),
+            ("tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq", Bech32Error::MixedCase),
+            ("bc1gmk9yu", Bech32Error::InvalidLength),
+        ];
+
+        for (address, error) in cases {
+            assert_eq!(decode_segwit_address(address), Err(error), "{}", address);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, Secp256k1, SecretKey};
+use std::str::FromStr;
+
+use crate::address::{Address, AddressError, AddressType};
+use crate::key::{Key, PrivateKey, PublicKey};
+use crate::utils::compact_size;
+
+/// Prefix of signed messages, so signatures can't be replayed as transaction signatures.
+pub const MESSAGE_PREFIX: &str = "Bitcoin Signed Message:\n";
+
+#[derive(Debug, PartialEq)]
+pub enum MessageError {
+    InvalidBase64(base64::DecodeError),
+    InvalidLength(usize),
+    InvalidHeader(u8),
+    InvalidSignature(secp256k1::Error),
+    InvalidAddress(AddressError),
+    UnsupportedAddress(AddressType),
+    UncompressedSegwit,
+}
+
+impl From<base64::DecodeError> for MessageError {
+    fn from(err: base64::DecodeError) -> Self {
+        MessageError::InvalidBase64(err)
+    }
+}
+
+impl From<secp256k1::Error> for MessageError {
+    fn from(err: secp256k1::Error) -> Self {
+        MessageError::InvalidSignature(err)
+    }
+}
+
+impl From<AddressError> for MessageError {
+    fn from(err: AddressError) -> Self {
+        MessageError::InvalidAddress(err)
+    }
+}
+
+/// The address type a signature commits to in its header byte (BIP137)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureType {
+    P2pkhUncompressed,
+    P2pkh,
+    P2shP2wpkh,
+    P2wpkh,
+}
+
+impl SignatureType {
+    /// Returns the header byte for recovery id 0, the recovery id being added to it.
+    pub fn header_base(self) -> u8 {
+        match self {
+            SignatureType::P2pkhUncompressed => 27,
+            SignatureType::P2pkh => 31,
+            SignatureType::P2shP2wpkh => 35,
+            SignatureType::P2wpkh => 39,
+        }
+    }
+
+    /// Splits a header byte into the signature type and the recovery id.
+    pub fn from_header(header: u8) -> Result<(Self, i32), MessageError> {
+        let signature_type = match header {
+            27..=30 => SignatureType::P2pkhUncompressed,
+            31..=34 => SignatureType::P2pkh,
+            35..=38 => SignatureType::P2shP2wpkh,
+            39..=42 => SignatureType::P2wpkh,
+            _ => return Err(MessageError::InvalidHeader(header)),
+        };
+
+        Ok((signature_type, (header - signature_type.header_base()) as i32))
+    }
+
+    /// Returns true if the signature recovers a compressed public key.
+    pub fn is_compressed(self) -> bool {
+        self != SignatureType::P2pkhUncompressed
+    }
+}
+
+/// Returns the double SHA256 of the prefixed message, the hash actually signed.
+///
+/// # Arguments
+///
+/// * `message` - The message as a string slice.
+pub fn message_hash(message: &str) -> Vec<u8> {
+    let mut data = compact_size(MESSAGE_PREFIX.len() as u64);
+    data.extend_from_slice(MESSAGE_PREFIX.as_bytes());
+    data.append(&mut compact_size(message.len() as u64));
+    data.extend_from_slice(message.as_bytes());
+
+    data.sha256().sha256()
+}
+
+/// Signs a message, returning the base64 encoded 65-byte compact recoverable signature.
+///
+/// # Arguments
+///
+/// * `private_key` - The signing key.
+/// * `message` - The message as a string slice.
+/// * `signature_type` - The address type recorded in the header byte.
+pub fn sign_message(
+    private_key: &PrivateKey,
+    message: &str,
+    signature_type: SignatureType,
+) -> Result<String, MessageError> {
+    let secp = Secp256k1::signing_only();
+    let secret_key = SecretKey::from_slice(&private_key.key)?;
+    let hash = Message::from_slice(&message_hash(message))?;
+
+    let (recovery_id, compact) = secp
+        .sign_ecdsa_recoverable(&hash, &secret_key)
+        .serialize_compact();
+
+    let mut signature = vec![signature_type.header_base() + recovery_id.to_i32() as u8];
+    signature.extend_from_slice(&compact);
+
+    Ok(base64::encode(signature))
+}
+
+/// Recovers the public key that signed a message, along with the type in the header byte.
+///
+/// # Arguments
+///
+/// * `message` - The message as a string slice.
+/// * `signature` - The base64 encoded compact recoverable signature.
+pub fn recover_public_key(message: &str, signature: &str) -> Result<(PublicKey, SignatureType), MessageError> {
+    let signature = base64::decode(signature.trim())?;
+
+    if signature.len() != 65 {
+        return Err(MessageError::InvalidLength(signature.len()));
+    }
+
+    let (signature_type, recovery_id) = SignatureType::from_header(signature[0])?;
+    let recoverable = RecoverableSignature::from_compact(&signature[1..], RecoveryId::from_i32(recovery_id)?)?;
+
+    let secp = Secp256k1::verification_only();
+    let hash = Message::from_slice(&message_hash(message))?;
+    let pubkey = secp.recover_ecdsa(&hash, &recoverable)?;
+
+    Ok((PublicKey::from_secp256k1(&pubkey, Default::default()), signature_type))
+}
+
+/// Returns true if the signature was made by the key behind a P2PKH, P2SH-P2WPKH or P2WPKH
+/// address.
+///
+/// Segwit addresses are also accepted with a compressed P2PKH header, as some wallets produce.
+///
+/// # Arguments
+///
+/// * `address` - The address of the signer.
+/// * `message` - The message as a string slice.
+/// * `signature` - The base64 encoded compact recoverable signature.
+pub fn verify_message(address: &str, message: &str, signature: &str) -> Result<bool, MessageError> {
+    let address = Address::from_str(address)?;
+    let (pubkey, signature_type) = recover_public_key(message, signature)?;
+
+    let hash = match address.address_type {
+        AddressType::P2pkh if signature_type.is_compressed() => pubkey.compressed.hash160(),
+        AddressType::P2pkh => pubkey.uncompressed.hash160(),
+        AddressType::P2sh | AddressType::P2wpkh if !signature_type.is_compressed() => {
+            return Err(MessageError::UncompressedSegwit)
+        }
+        AddressType::P2sh => pubkey.p2wpkh_redeem_script().hash160(),
+        AddressType::P2wpkh => pubkey.compressed.hash160(),
+        address_type => return Err(MessageError::UnsupportedAddress(address_type)),
+    };
+
+    Ok(hash == address.payload)
+}
+
+#[cfg(test)]
+mod bip137_tests {
+    use super::*;
+    use crate::key::{ADDRESS_FROM_COMPRESSED, ADDRESS_FROM_UNCOMPRESSED, P2SH_P2WPKH_ADDRESS, P2WPKH_ADDRESS, PRIVATE_KEY};
+
+    const MESSAGE: &str = "Hello, World!";
+    const SIGNATURE: &str = "IESWxySuThtvciDVPW1019NPS/kF7bVlOwSSCEpVv68cfnnZY/c953z2DfH29o3H1mVcJe3vkBR+qMnMFY7M91Y=";
+
+    fn sign(signature_type: SignatureType) -> String {
+        sign_message(&PrivateKey::from_str(PRIVATE_KEY).unwrap(), MESSAGE, signature_type).unwrap()
+    }
+
+    #[test]
+    fn should_return_expected_signature() {
+        assert_eq!(sign(SignatureType::P2pkh), SIGNATURE);
+        assert_eq!(
+            sign(SignatureType::P2wpkh),
+            "KESWxySuThtvciDVPW1019NPS/kF7bVlOwSSCEpVv68cfnnZY/c953z2DfH29o3H1mVcJe3vkBR+qMnMFY7M91Y="
+        );
+    }
+
+    #[test]
+    fn should_verify_signature_for_each_address_type() {
+        assert!(verify_message(ADDRESS_FROM_COMPRESSED, MESSAGE, SIGNATURE).unwrap());
+        assert!(verify_message(ADDRESS_FROM_UNCOMPRESSED, MESSAGE, &sign(SignatureType::P2pkhUncompressed)).unwrap());
+        assert!(verify_message(P2SH_P2WPKH_ADDRESS, MESSAGE, &sign(SignatureType::P2shP2wpkh)).unwrap());
+        assert!(verify_message(P2WPKH_ADDRESS, MESSAGE, &sign(SignatureType::P2wpkh)).unwrap());
+        assert!(verify_message(P2WPKH_ADDRESS, MESSAGE, SIGNATURE).unwrap());
+    }
+
+    #[test]
+    fn should_not_verify_other_message_or_address() {
+        assert!(!verify_message(ADDRESS_FROM_COMPRESSED, "Hello, World?", SIGNATURE).unwrap());
+        assert!(!verify_message(ADDRESS_FROM_UNCOMPRESSED, MESSAGE, SIGNATURE).unwrap());
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_signature() {
+        assert!(matches!(verify_message(ADDRESS_FROM_COMPRESSED, MESSAGE, "not base64!"), Err(MessageError::InvalidBase64(_))));
+        assert_eq!(verify_message(ADDRESS_FROM_COMPRESSED, MESSAGE, "IESWxySu"), Err(MessageError::InvalidLength(6)));
+        assert_eq!(
+            verify_message(ADDRESS_FROM_COMPRESSED, MESSAGE, &SIGNATURE.replacen('I', "A", 1)),
+            Err(MessageError::InvalidHeader(0))
+        );
+        assert_eq!(
+            verify_message(P2WPKH_ADDRESS, MESSAGE, &sign(SignatureType::P2pkhUncompressed)),
+            Err(MessageError::UncompressedSegwit)
+        );
+    }
+
+    #[test]
+    fn should_return_expected_message_hash() {
+        assert_eq!(
+            hex::encode(message_hash("")),
+            "80e795d4a4caadd7047af389d9f7f220562feb6196032e2131e10563352c4bcc"
+        );
+    }
+}
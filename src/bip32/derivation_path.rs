@@ -0,0 +1,105 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bip32::Bip32Error;
+
+/// Offset of hardened child numbers, written with a `'` or `h` suffix in paths.
+pub const HARDENED: u32 = 0x8000_0000;
+
+/// A BIP32 derivation path such as `m/84'/0'/0'/0/5`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DerivationPath {
+    /// Child numbers from the master key, hardened ones offset by `HARDENED`.
+    pub steps: Vec<u32>,
+}
+
+impl DerivationPath {
+    /// Returns the path extended by one child number.
+    pub fn child(&self, index: u32) -> Self {
+        let mut steps = self.steps.clone();
+        steps.push(index);
+
+        DerivationPath { steps }
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Bip32Error;
+
+    /// Parses a path starting with `m`, hardened steps suffixed by `'`, `h` or `H`.
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let invalid = || Bip32Error::InvalidPath(path.to_string());
+
+        let mut parts = path.split('/');
+
+        if parts.next() != Some("m") {
+            return Err(invalid());
+        }
+
+        let steps = parts
+            .map(|part| {
+                let (index, hardened) = match part.strip_suffix(['\'', 'h', 'H']) {
+                    Some(index) => (index, true),
+                    None => (part, false),
+                };
+
+                let index: u32 = index.parse().map_err(|_| invalid())?;
+
+                match (index < HARDENED, hardened) {
+                    (true, true) => Ok(index + HARDENED),
+                    (true, false) => Ok(index),
+                    (false, _) => Err(invalid()),
+                }
+            })
+            .collect::<Result<Vec<u32>, Bip32Error>>()?;
+
+        Ok(DerivationPath { steps })
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m")?;
+
+        for step in &self.steps {
+            match *step >= HARDENED {
+                true => write!(f, "/{}'", step - HARDENED)?,
+                false => write!(f, "/{}", step)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod derivation_path_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_path() {
+        assert_eq!(
+            DerivationPath::from_str("m/84'/0h/0H/0/5").unwrap().steps,
+            vec![84 + HARDENED, HARDENED, HARDENED, 0, 5],
+        );
+        assert_eq!(DerivationPath::from_str("m").unwrap().steps, vec![]);
+    }
+
+    #[test]
+    fn should_format_path() {
+        let path = DerivationPath::from_str("m/84h/0h/0h/0/5").unwrap();
+
+        assert_eq!(path.to_string(), "m/84'/0'/0'/0/5");
+        assert_eq!(path.child(6).to_string(), "m/84'/0'/0'/0/5/6");
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_path() {
+        for path in ["", "84'/0'", "m/", "m/a", "m/-1", "m/2147483648", "m/0''"] {
+            assert_eq!(
+                DerivationPath::from_str(path),
+                Err(Bip32Error::InvalidPath(path.to_string())),
+            );
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use secp256k1::SecretKey;
+
+use crate::base58encoder::base58check_encode;
+use crate::bip32::{deserialize, hmac_sha512, serialize, Bip32Error, DerivationPath, ExtendedPublicKey, HARDENED};
+use crate::key::{Key, PrivateKey, PublicKey};
+use crate::network::Network;
+
+/// A BIP32 extended private key: a private key and the chain code needed to derive its children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedPrivateKey {
+    pub network: Network,
+    pub depth: u8,
+    pub parent_fingerprint: Vec<u8>,
+    pub child_number: u32,
+    pub chain_code: Vec<u8>,
+    pub private_key: PrivateKey,
+}
+
+impl ExtendedPrivateKey {
+    /// Returns the master key of a wallet, given its seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Between 16 and 64 bytes of seed, e.g. derived from a BIP39 mnemonic.
+    /// * `network` - Network the derived keys belong to.
+    pub fn from_seed(seed: &[u8], network: Network) -> Result<Self, Bip32Error> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(Bip32Error::InvalidSeedLength(seed.len()));
+        }
+
+        let (key, chain_code) = hmac_sha512(b"Bitcoin seed", seed);
+
+        Ok(ExtendedPrivateKey {
+            network,
+            depth: 0,
+            parent_fingerprint: vec![0x00; 4],
+            child_number: 0,
+            chain_code,
+            private_key: ExtendedPrivateKey::to_private_key(&key, network)?,
+        })
+    }
+
+    /// Parses a base58check serialized extended private key (xprv or tprv).
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded` - The extended private key as a string slice.
+    pub fn from_base58check(encoded: &str) -> Result<Self, Bip32Error> {
+        let fields = deserialize(encoded)?;
+
+        if !fields.private || fields.key_data[0] != 0x00 {
+            return Err(Bip32Error::InvalidKeyData);
+        }
+
+        Ok(ExtendedPrivateKey {
+            network: fields.network,
+            depth: fields.depth,
+            parent_fingerprint: fields.parent_fingerprint,
+            child_number: fields.child_number,
+            chain_code: fields.chain_code,
+            private_key: ExtendedPrivateKey::to_private_key(&fields.key_data[1..], fields.network)?,
+        })
+    }
+
+    /// Returns the 78-byte BIP32 serialization of the key.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut key_data = vec![0x00];
+        key_data.extend_from_slice(&self.private_key.key);
+
+        serialize(
+            self.network.xprv_version(),
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &key_data,
+        )
+    }
+
+    /// Returns the key serialized and base58check encoded, i.e. an xprv or tprv string.
+    pub fn as_base58check(&self) -> String {
+        let data = self.serialize();
+
+        base58check_encode(&data[0..4], &data[4..])
+    }
+
+    fn to_private_key(key: &[u8], network: Network) -> Result<PrivateKey, Bip32Error> {
+        let secret_key = SecretKey::from_slice(key)?;
+
+        Ok(PrivateKey {
+            key: secret_key.secret_bytes().to_vec(),
+            compressed: Some(true),
+            network,
+        })
+    }
+
+    /// Returns the child key at `index`, hardened if `index` is at least `HARDENED`.
+    pub fn derive_child(&self, index: u32) -> Result<Self, Bip32Error> {
+        if self.depth == u8::MAX {
+            return Err(Bip32Error::MaxDepthExceeded);
+        }
+
+        let mut data = match index >= HARDENED {
+            true => {
+                let mut data = vec![0x00];
+                data.extend_from_slice(&self.private_key.key);
+                data
+            }
+            false => self.public_key().compressed,
+        };
+        data.extend_from_slice(&index.to_be_bytes());
+
+        let (tweak, chain_code) = hmac_sha512(&self.chain_code, &data);
+
+        let mut secret_key = SecretKey::from_slice(&self.private_key.key)?;
+        secret_key.add_assign(&tweak)?;
+
+        Ok(ExtendedPrivateKey {
+            network: self.network,
+            depth: self.depth + 1,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            private_key: ExtendedPrivateKey::to_private_key(&secret_key.secret_bytes(), self.network)?,
+        })
+    }
+
+    /// Returns the descendant key at `path`, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, Bip32Error> {
+        path.steps
+            .iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(*index))
+    }
+
+    /// Returns the public key paired with this private key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_private_key(self.private_key.clone())
+    }
+
+    /// Returns the extended public key, which can derive the non-hardened children public keys.
+    pub fn to_extended_public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            network: self.network,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint.clone(),
+            child_number: self.child_number,
+            chain_code: self.chain_code.clone(),
+            public_key: self.public_key(),
+        }
+    }
+
+    /// Returns the first four bytes of the hash160 of the compressed public key.
+    pub fn fingerprint(&self) -> Vec<u8> {
+        self.public_key().compressed.hash160()[0..4].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod extended_private_key_tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+    fn derive(path: &str) -> ExtendedPrivateKey {
+        ExtendedPrivateKey::from_seed(&hex::decode(SEED).unwrap(), Network::Mainnet)
+            .unwrap()
+            .derive_path(&DerivationPath::from_str(path).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn should_return_expected_master_key() {
+        let master = derive("m");
+
+        assert_eq!(
+            hex::encode(&master.private_key.key),
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35",
+        );
+        assert_eq!(
+            hex::encode(&master.chain_code),
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508",
+        );
+        assert_eq!(hex::encode(master.fingerprint()), "3442193e");
+        assert_eq!(master.depth, 0);
+    }
+
+    #[test]
+    fn should_derive_hardened_child() {
+        let child = derive("m/0'");
+
+        assert_eq!(
+            hex::encode(&child.private_key.key),
+            "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+        );
+        assert_eq!(
+            hex::encode(&child.chain_code),
+            "47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141",
+        );
+        assert_eq!(hex::encode(&child.parent_fingerprint), "3442193e");
+        assert_eq!(child.child_number, HARDENED);
+    }
+
+    #[test]
+    fn should_derive_non_hardened_child() {
+        let child = derive("m/0'/1");
+
+        assert_eq!(
+            hex::encode(&child.private_key.key),
+            "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368",
+        );
+        assert_eq!(
+            hex::encode(&child.chain_code),
+            "2a7857631386ba23dacac34180dd1983734e444fdbf774041578e9b6adb37c19",
+        );
+    }
+
+    #[test]
+    fn should_derive_deep_path() {
+        let child = derive("m/0'/1/2'/2/1000000000");
+
+        assert_eq!(
+            hex::encode(&child.private_key.key),
+            "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8",
+        );
+        assert_eq!(
+            hex::encode(&child.public_key().compressed),
+            "022a471424da5e657499d1ff51cb43c47481a03b1e77f951fe64cec9f5a48f7011",
+        );
+        assert_eq!(child.depth, 5);
+    }
+
+    #[test]
+    fn should_serialize_to_xprv() {
+        assert_eq!(
+            derive("m").as_base58check(),
+            "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+        );
+        assert_eq!(
+            derive("m/0'").as_base58check(),
+            "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7",
+        );
+        assert_eq!(derive("m").serialize().len(), 78);
+    }
+
+    #[test]
+    fn should_serialize_to_tprv() {
+        let mut master = derive("m");
+        master.network = Network::Testnet;
+
+        assert_eq!(
+            master.as_base58check(),
+            "tprv8ZgxMBicQKsPeDgjzdC36fs6bMjGApWDNLR9erAXMs5skhMv36j9MV5ecvfavji5khqjWaWSFhN3YcCUUdiKH6isR4Pwy3U5y5egddBr16m",
+        );
+    }
+
+    #[test]
+    fn should_parse_xprv() {
+        let key = derive("m/0'");
+
+        assert_eq!(
+            ExtendedPrivateKey::from_base58check(&key.as_base58check()).unwrap(),
+            key,
+        );
+        assert_eq!(
+            ExtendedPrivateKey::from_base58check(
+                "tprv8ZgxMBicQKsPeDgjzdC36fs6bMjGApWDNLR9erAXMs5skhMv36j9MV5ecvfavji5khqjWaWSFhN3YcCUUdiKH6isR4Pwy3U5y5egddBr16m"
+            )
+            .unwrap()
+            .network,
+            Network::Testnet,
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_parsing_xpub_as_xprv() {
+        assert_eq!(
+            ExtendedPrivateKey::from_base58check(
+                "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+            ),
+            Err(Bip32Error::InvalidKeyData),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_length() {
+        assert_eq!(
+            ExtendedPrivateKey::from_base58check("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy"),
+            Err(Bip32Error::InvalidLength(21)),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_zero_depth_with_parent() {
+        let mut key = derive("m/0'");
+        key.depth = 0;
+
+        assert_eq!(
+            ExtendedPrivateKey::from_base58check(&key.as_base58check()),
+            Err(Bip32Error::InvalidKeyData),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_seed_length() {
+        assert_eq!(
+            ExtendedPrivateKey::from_seed(&[0x00; 15], Network::Mainnet),
+            Err(Bip32Error::InvalidSeedLength(15)),
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use secp256k1::Secp256k1;
+
+use crate::base58encoder::base58check_encode;
+use crate::bip32::{deserialize, hmac_sha512, serialize, Bip32Error, DerivationPath, HARDENED};
+use crate::key::{Key, PublicKey};
+use crate::network::Network;
+
+/// A BIP32 extended public key: a public key and the chain code needed to derive its
+/// non-hardened children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedPublicKey {
+    pub network: Network,
+    pub depth: u8,
+    pub parent_fingerprint: Vec<u8>,
+    pub child_number: u32,
+    pub chain_code: Vec<u8>,
+    pub public_key: PublicKey,
+}
+
+impl ExtendedPublicKey {
+    /// Parses a base58check serialized extended public key (xpub or tpub).
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded` - The extended public key as a string slice.
+    pub fn from_base58check(encoded: &str) -> Result<Self, Bip32Error> {
+        let fields = deserialize(encoded)?;
+
+        if fields.private {
+            return Err(Bip32Error::InvalidKeyData);
+        }
+
+        let pubkey = secp256k1::PublicKey::from_slice(&fields.key_data)?;
+
+        Ok(ExtendedPublicKey {
+            network: fields.network,
+            depth: fields.depth,
+            parent_fingerprint: fields.parent_fingerprint,
+            child_number: fields.child_number,
+            chain_code: fields.chain_code,
+            public_key: PublicKey {
+                compressed: pubkey.serialize().to_vec(),
+                uncompressed: pubkey.serialize_uncompressed().to_vec(),
+                network: fields.network,
+            },
+        })
+    }
+
+    /// Returns the 78-byte BIP32 serialization of the key.
+    pub fn serialize(&self) -> Vec<u8> {
+        serialize(
+            self.network.xpub_version(),
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &self.public_key.compressed,
+        )
+    }
+
+    /// Returns the key serialized and base58check encoded, i.e. an xpub or tpub string.
+    pub fn as_base58check(&self) -> String {
+        let data = self.serialize();
+
+        base58check_encode(&data[0..4], &data[4..])
+    }
+
+    /// Returns the child key at `index`, which must not be hardened.
+    pub fn derive_child(&self, index: u32) -> Result<Self, Bip32Error> {
+        if index >= HARDENED {
+            return Err(Bip32Error::HardenedDerivationFromPublicKey);
+        }
+
+        if self.depth == u8::MAX {
+            return Err(Bip32Error::MaxDepthExceeded);
+        }
+
+        let mut data = self.public_key.compressed.clone();
+        data.extend_from_slice(&index.to_be_bytes());
+
+        let (tweak, chain_code) = hmac_sha512(&self.chain_code, &data);
+
+        let secp = Secp256k1::verification_only();
+        let mut pubkey = secp256k1::PublicKey::from_slice(&self.public_key.compressed)?;
+        pubkey.add_exp_assign(&secp, &tweak)?;
+
+        Ok(ExtendedPublicKey {
+            network: self.network,
+            depth: self.depth + 1,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            public_key: PublicKey {
+                compressed: pubkey.serialize().to_vec(),
+                uncompressed: pubkey.serialize_uncompressed().to_vec(),
+                network: self.network,
+            },
+        })
+    }
+
+    /// Returns the descendant key at `path`, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, Bip32Error> {
+        path.steps
+            .iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(*index))
+    }
+
+    /// Returns the first four bytes of the hash160 of the compressed public key.
+    pub fn fingerprint(&self) -> Vec<u8> {
+        self.public_key.compressed.clone().hash160()[0..4].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod extended_public_key_tests {
+    use super::*;
+    use crate::bip32::ExtendedPrivateKey;
+    use std::str::FromStr;
+
+    fn master() -> ExtendedPrivateKey {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+
+        ExtendedPrivateKey::from_seed(&seed, Network::Mainnet).unwrap()
+    }
+
+    #[test]
+    fn should_match_private_derivation() {
+        let account = master()
+            .derive_path(&DerivationPath::from_str("m/0'").unwrap())
+            .unwrap();
+        let path = DerivationPath::from_str("m/1/2/3").unwrap();
+
+        assert_eq!(
+            account.to_extended_public_key().derive_path(&path).unwrap(),
+            account.derive_path(&path).unwrap().to_extended_public_key(),
+        );
+    }
+
+    #[test]
+    fn should_serialize_to_xpub() {
+        let account = master()
+            .derive_path(&DerivationPath::from_str("m/0'").unwrap())
+            .unwrap();
+
+        assert_eq!(
+            master().to_extended_public_key().as_base58check(),
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+        );
+        assert_eq!(
+            account.to_extended_public_key().as_base58check(),
+            "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+        );
+    }
+
+    #[test]
+    fn should_parse_xpub() {
+        let xpub = master().to_extended_public_key();
+
+        assert_eq!(
+            ExtendedPublicKey::from_base58check(&xpub.as_base58check()).unwrap(),
+            xpub,
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_parsing_xprv_as_xpub() {
+        assert_eq!(
+            ExtendedPublicKey::from_base58check(&master().as_base58check()),
+            Err(Bip32Error::InvalidKeyData),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_unknown_version() {
+        let mut data = master().to_extended_public_key().serialize();
+        data[3] = 0x00;
+
+        assert_eq!(
+            ExtendedPublicKey::from_base58check(&base58check_encode(&data[0..4], &data[4..])),
+            Err(Bip32Error::UnknownVersion(vec![0x04, 0x88, 0xb2, 0x00])),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_hardened() {
+        assert_eq!(
+            master().to_extended_public_key().derive_child(HARDENED),
+            Err(Bip32Error::HardenedDerivationFromPublicKey),
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crypto::{hmac::Hmac, mac::Mac, sha2::Sha512};
+
+use crate::base58decoder::{base58check_decode, Base58Error};
+use crate::network::Network;
+
+mod derivation_path;
+pub use derivation_path::{DerivationPath, HARDENED};
+
+mod extended_private_key;
+pub use extended_private_key::ExtendedPrivateKey;
+
+mod extended_public_key;
+pub use extended_public_key::ExtendedPublicKey;
+
+mod purpose;
+pub use purpose::Purpose;
+
+pub mod slip132;
+
+#[derive(Debug, PartialEq)]
+pub enum Bip32Error {
+    InvalidSeedLength(usize),
+    InvalidPath(String),
+    InvalidKey(secp256k1::Error),
+    HardenedDerivationFromPublicKey,
+    MaxDepthExceeded,
+    InvalidBase58(Base58Error),
+    InvalidLength(usize),
+    UnknownVersion(Vec<u8>),
+    IncompatibleVersion(String),
+    InvalidKeyData,
+    /// An index meant to be hardened by adding 2^31 is already 2^31 or above.
+    IndexOutOfRange(u32),
+}
+
+impl From<secp256k1::Error> for Bip32Error {
+    fn from(err: secp256k1::Error) -> Self {
+        Bip32Error::InvalidKey(err)
+    }
+}
+
+impl From<Base58Error> for Bip32Error {
+    fn from(err: Base58Error) -> Self {
+        Bip32Error::InvalidBase58(err)
+    }
+}
+
+/// The fields shared by serialized extended private and public keys.
+struct Serialized {
+    network: Network,
+    private: bool,
+    depth: u8,
+    parent_fingerprint: Vec<u8>,
+    child_number: u32,
+    chain_code: Vec<u8>,
+    key_data: Vec<u8>,
+}
+
+/// Returns the 78-byte BIP32 serialization of an extended key.
+fn serialize(
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: &[u8],
+    child_number: u32,
+    chain_code: &[u8],
+    key_data: &[u8],
+) -> Vec<u8> {
+    let mut data = version.to_vec();
+    data.push(depth);
+    data.extend_from_slice(parent_fingerprint);
+    data.extend_from_slice(&child_number.to_be_bytes());
+    data.extend_from_slice(chain_code);
+    data.extend_from_slice(key_data);
+
+    data
+}
+
+/// Decodes a base58check extended key and splits its 78 bytes into fields.
+fn deserialize(encoded: &str) -> Result<Serialized, Bip32Error> {
+    let data = base58check_decode(encoded)?;
+
+    if data.len() != 78 {
+        return Err(Bip32Error::InvalidLength(data.len()));
+    }
+
+    let version = &data[0..4];
+
+    let (network, private) = [Network::Mainnet, Network::Testnet]
+        .iter()
+        .find_map(|network| match version {
+            v if v == network.xprv_version() => Some((*network, true)),
+            v if v == network.xpub_version() => Some((*network, false)),
+            _ => None,
+        })
+        .ok_or_else(|| Bip32Error::UnknownVersion(version.to_vec()))?;
+
+    let depth = data[4];
+    let parent_fingerprint = data[5..9].to_vec();
+    let child_number = u32::from_be_bytes([data[9], data[10], data[11], data[12]]);
+
+    if depth == 0 && (parent_fingerprint != [0x00; 4] || child_number != 0) {
+        return Err(Bip32Error::InvalidKeyData);
+    }
+
+    Ok(Serialized {
+        network,
+        private,
+        depth,
+        parent_fingerprint,
+        child_number,
+        chain_code: data[13..45].to_vec(),
+        key_data: data[45..78].to_vec(),
+    })
+}
+
+/// Returns HMAC-SHA512 of `data` keyed with `key`, split into its left and right 32 bytes.
+fn hmac_sha512(key: &[u8], data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut hmac = Hmac::new(Sha512::new(), key);
+    hmac.input(data);
+
+    let mut result = hmac.result().code().to_vec();
+    let right = result.split_off(32);
+
+    (result, right)
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bip32::{Bip32Error, DerivationPath, HARDENED};
+use crate::key::PublicKey;
+use crate::network::Network;
+
+/// The standard derivation schemes, named after the BIP defining their purpose level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    /// BIP44, legacy P2PKH addresses.
+    Bip44,
+    /// BIP49, nested segwit P2SH-P2WPKH addresses.
+    Bip49,
+    /// BIP84, native segwit P2WPKH addresses.
+    Bip84,
+    /// BIP86, taproot P2TR addresses.
+    Bip86,
+}
+
+impl Purpose {
+    /// Returns the purpose level of the derivation path.
+    pub fn number(&self) -> u32 {
+        match self {
+            Purpose::Bip44 => 44,
+            Purpose::Bip49 => 49,
+            Purpose::Bip84 => 84,
+            Purpose::Bip86 => 86,
+        }
+    }
+
+    /// Returns the path of an account: `m/purpose'/coin_type'/account'`.
+    ///
+    /// The coin type is 0 on mainnet and 1 on every test network. Accounts are hardened, so
+    /// they must be below 2^31.
+    pub fn account_path(&self, network: Network, account: u32) -> Result<DerivationPath, Bip32Error> {
+        let coin_type = match network {
+            Network::Mainnet => 0,
+            _ => 1,
+        };
+
+        if account >= HARDENED {
+            return Err(Bip32Error::IndexOutOfRange(account));
+        }
+
+        Ok(DerivationPath {
+            steps: vec![
+                self.number() + HARDENED,
+                coin_type + HARDENED,
+                account + HARDENED,
+            ],
+        })
+    }
+
+    /// Returns the address of `pubkey` for the script type of the purpose.
+    pub fn address(&self, pubkey: PublicKey) -> Result<String, secp256k1::Error> {
+        match self {
+            Purpose::Bip44 => Ok(pubkey.get_address_from_compressed()),
+            Purpose::Bip49 => Ok(pubkey.get_p2sh_p2wpkh_address()),
+            Purpose::Bip84 => Ok(pubkey.get_p2wpkh_address()),
+            Purpose::Bip86 => pubkey.get_p2tr_address(None),
+        }
+    }
+}
+
+impl FromStr for Purpose {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim_start_matches("bip") {
+            "44" => Ok(Purpose::Bip44),
+            "49" => Ok(Purpose::Bip49),
+            "84" => Ok(Purpose::Bip84),
+            "86" => Ok(Purpose::Bip86),
+            _ => Err(format!("unknown purpose: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Purpose {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BIP{}", self.number())
+    }
+}
+
+#[cfg(test)]
+mod purpose_tests {
+    use super::*;
+    use crate::bip32::ExtendedPrivateKey;
+    use crate::bip39::{Language, Mnemonic};
+
+    fn first_address(purpose: Purpose, network: Network, change: u32) -> String {
+        let seed = Mnemonic::from_phrase(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            Language::English,
+        )
+        .unwrap()
+        .to_seed("");
+
+        let key = ExtendedPrivateKey::from_seed(&seed, network)
+            .unwrap()
+            .derive_path(&purpose.account_path(network, 0).unwrap().child(change).child(0))
+            .unwrap();
+
+        purpose.address(key.public_key()).unwrap()
+    }
+
+    #[test]
+    fn should_return_account_path() {
+        assert_eq!(
+            Purpose::Bip84.account_path(Network::Mainnet, 0).unwrap().to_string(),
+            "m/84'/0'/0'",
+        );
+        assert_eq!(
+            Purpose::Bip49.account_path(Network::Testnet, 3).unwrap().to_string(),
+            "m/49'/1'/3'",
+        );
+        assert_eq!(
+            Purpose::Bip84.account_path(Network::Mainnet, HARDENED),
+            Err(Bip32Error::IndexOutOfRange(HARDENED)),
+        );
+    }
+
+    #[test]
+    fn should_derive_bip44_address() {
+        assert_eq!(
+            first_address(Purpose::Bip44, Network::Mainnet, 0),
+            "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+        );
+    }
+
+    #[test]
+    fn should_derive_bip49_address() {
+        assert_eq!(
+            first_address(Purpose::Bip49, Network::Testnet, 0),
+            "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2",
+        );
+    }
+
+    #[test]
+    fn should_derive_bip84_addresses() {
+        assert_eq!(
+            first_address(Purpose::Bip84, Network::Mainnet, 0),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+        );
+        assert_eq!(
+            first_address(Purpose::Bip84, Network::Mainnet, 1),
+            "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+        );
+    }
+
+    #[test]
+    fn should_derive_bip86_address() {
+        assert_eq!(
+            first_address(Purpose::Bip86, Network::Mainnet, 0),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        );
+    }
+
+    #[test]
+    fn should_parse_purpose() {
+        assert_eq!(Purpose::from_str("84"), Ok(Purpose::Bip84));
+        assert_eq!(Purpose::from_str("BIP86"), Ok(Purpose::Bip86));
+        assert!(Purpose::from_str("45").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+
+use crate::base58decoder::base58check_decode;
+use crate::base58encoder::base58check_encode;
+use crate::bip32::Bip32Error;
+use crate::network::Network;
+
+/// The output script type wallets derive from a SLIP-132 extended key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptType {
+    /// Legacy P2PKH, or P2SH multisig: xpub and tpub do not tell them apart.
+    P2pkhOrP2sh,
+    P2shP2wpkh,
+    P2shP2wsh,
+    P2wpkh,
+    P2wsh,
+}
+
+impl fmt::Display for ScriptType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ScriptType::P2pkhOrP2sh => "p2pkh or p2sh",
+            ScriptType::P2shP2wpkh => "p2wpkh nested in p2sh",
+            ScriptType::P2shP2wsh => "p2wsh nested in p2sh",
+            ScriptType::P2wpkh => "p2wpkh",
+            ScriptType::P2wsh => "p2wsh",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A registered SLIP-132 extended key version.
+#[derive(Debug, PartialEq, Eq)]
+pub struct KeyVersion {
+    /// The first four characters of the base58check string, e.g. "zpub".
+    pub prefix: &'static str,
+    pub version: [u8; 4],
+    pub network: Network,
+    pub private: bool,
+    pub script_type: ScriptType,
+}
+
+const fn key_version(
+    prefix: &'static str,
+    version: [u8; 4],
+    network: Network,
+    private: bool,
+    script_type: ScriptType,
+) -> KeyVersion {
+    KeyVersion { prefix, version, network, private, script_type }
+}
+
+/// The SLIP-132 registry for Bitcoin. Test networks are reported as `Network::Testnet`.
+pub const KEY_VERSIONS: [KeyVersion; 20] = [
+    key_version("xpub", [0x04, 0x88, 0xb2, 0x1e], Network::Mainnet, false, ScriptType::P2pkhOrP2sh),
+    key_version("xprv", [0x04, 0x88, 0xad, 0xe4], Network::Mainnet, true, ScriptType::P2pkhOrP2sh),
+    key_version("ypub", [0x04, 0x9d, 0x7c, 0xb2], Network::Mainnet, false, ScriptType::P2shP2wpkh),
+    key_version("yprv", [0x04, 0x9d, 0x78, 0x78], Network::Mainnet, true, ScriptType::P2shP2wpkh),
+    key_version("Ypub", [0x02, 0x95, 0xb4, 0x3f], Network::Mainnet, false, ScriptType::P2shP2wsh),
+    key_version("Yprv", [0x02, 0x95, 0xb0, 0x05], Network::Mainnet, true, ScriptType::P2shP2wsh),
+    key_version("zpub", [0x04, 0xb2, 0x47, 0x46], Network::Mainnet, false, ScriptType::P2wpkh),
+    key_version("zprv", [0x04, 0xb2, 0x43, 0x0c], Network::Mainnet, true, ScriptType::P2wpkh),
+    key_version("Zpub", [0x02, 0xaa, 0x7e, 0xd3], Network::Mainnet, false, ScriptType::P2wsh),
+    key_version("Zprv", [0x02, 0xaa, 0x7a, 0x99], Network::Mainnet, true, ScriptType::P2wsh),
+    key_version("tpub", [0x04, 0x35, 0x87, 0xcf], Network::Testnet, false, ScriptType::P2pkhOrP2sh),
+    key_version("tprv", [0x04, 0x35, 0x83, 0x94], Network::Testnet, true, ScriptType::P2pkhOrP2sh),
+    key_version("upub", [0x04, 0x4a, 0x52, 0x62], Network::Testnet, false, ScriptType::P2shP2wpkh),
+    key_version("uprv", [0x04, 0x4a, 0x4e, 0x28], Network::Testnet, true, ScriptType::P2shP2wpkh),
+    key_version("Upub", [0x02, 0x42, 0x89, 0xef], Network::Testnet, false, ScriptType::P2shP2wsh),
+    key_version("Uprv", [0x02, 0x42, 0x85, 0xb5], Network::Testnet, true, ScriptType::P2shP2wsh),
+    key_version("vpub", [0x04, 0x5f, 0x1c, 0xf6], Network::Testnet, false, ScriptType::P2wpkh),
+    key_version("vprv", [0x04, 0x5f, 0x18, 0xbc], Network::Testnet, true, ScriptType::P2wpkh),
+    key_version("Vpub", [0x02, 0x57, 0x54, 0x83], Network::Testnet, false, ScriptType::P2wsh),
+    key_version("Vprv", [0x02, 0x57, 0x50, 0x48], Network::Testnet, true, ScriptType::P2wsh),
+];
+
+impl KeyVersion {
+    /// Returns the registered version matching the first four bytes of a serialized key.
+    pub fn from_version(version: &[u8]) -> Option<&'static KeyVersion> {
+        KEY_VERSIONS.iter().find(|v| v.version == version)
+    }
+
+    /// Returns the registered version with the given prefix, e.g. "zpub".
+    pub fn from_prefix(prefix: &str) -> Option<&'static KeyVersion> {
+        KEY_VERSIONS.iter().find(|v| v.prefix == prefix)
+    }
+
+    /// Returns the plain BIP32 version (xpub, xprv, tpub or tprv) of the same network and kind.
+    pub fn standard(&self) -> &'static KeyVersion {
+        KEY_VERSIONS
+            .iter()
+            .find(|v| {
+                v.network == self.network
+                    && v.private == self.private
+                    && v.script_type == ScriptType::P2pkhOrP2sh
+            })
+            .unwrap()
+    }
+}
+
+/// Returns the registered version of a base58check extended key.
+///
+/// # Arguments
+///
+/// * `encoded` - A serialized extended key, e.g. an xpub or a zprv.
+pub fn key_version_of(encoded: &str) -> Result<&'static KeyVersion, Bip32Error> {
+    let data = base58check_decode(encoded)?;
+
+    if data.len() != 78 {
+        return Err(Bip32Error::InvalidLength(data.len()));
+    }
+
+    KeyVersion::from_version(&data[0..4]).ok_or_else(|| Bip32Error::UnknownVersion(data[0..4].to_vec()))
+}
+
+/// Re-encodes an extended key with other version bytes, leaving the key itself unchanged.
+///
+/// # Arguments
+///
+/// * `encoded` - A serialized extended key, e.g. a zpub.
+/// * `target` - The target version; must be of the same network and kind (public or private).
+pub fn convert(encoded: &str, target: &KeyVersion) -> Result<String, Bip32Error> {
+    let source = key_version_of(encoded)?;
+
+    if source.network != target.network || source.private != target.private {
+        return Err(Bip32Error::IncompatibleVersion(target.prefix.to_string()));
+    }
+
+    let data = base58check_decode(encoded)?;
+
+    Ok(base58check_encode(&target.version, &data[4..]))
+}
+
+#[cfg(test)]
+mod slip132_tests {
+    use super::*;
+
+    const ZPUB: &str = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+    const XPUB: &str = "xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V";
+    const VPUB: &str = "vpub5YvMuJNjRSYon44z9QmCfdf8SqJRVNvz6m55Qy5iVjZQxDfUgtiQjnc7CC1fAbED2tAGCZRERUfvtn2DstZGU6HMns6dXXH2wujSc2wfi2x";
+    const TPUB: &str = "tpubDCxX2sYFS5bDkSe5GKKYHjBW7tgyN1R3UchpLJvdbf54ohxeGRtd8MbDUe1cguVHe4vnK68DsuD5MXjxi9EXx16rb9EnNsaF5KT99CinaJz";
+
+    #[test]
+    fn should_convert_between_versions() {
+        assert_eq!(convert(ZPUB, KeyVersion::from_prefix("xpub").unwrap()).unwrap(), XPUB);
+        assert_eq!(convert(XPUB, KeyVersion::from_prefix("zpub").unwrap()).unwrap(), ZPUB);
+        assert_eq!(convert(VPUB, KeyVersion::from_prefix("tpub").unwrap()).unwrap(), TPUB);
+    }
+
+    #[test]
+    fn should_encode_with_expected_prefixes() {
+        let xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+        let tprv = "tprv8ZgxMBicQKsPeDgjzdC36fs6bMjGApWDNLR9erAXMs5skhMv36j9MV5ecvfavji5khqjWaWSFhN3YcCUUdiKH6isR4Pwy3U5y5egddBr16m";
+
+        for version in KEY_VERSIONS.iter() {
+            let source = match (version.network, version.private) {
+                (Network::Mainnet, true) => xprv,
+                (Network::Mainnet, false) => XPUB,
+                (_, true) => tprv,
+                (_, false) => TPUB,
+            };
+
+            assert!(convert(source, version).unwrap().starts_with(version.prefix));
+        }
+    }
+
+    #[test]
+    fn should_report_script_type() {
+        assert_eq!(key_version_of(ZPUB).unwrap().script_type, ScriptType::P2wpkh);
+        assert_eq!(key_version_of(VPUB).unwrap().network, Network::Testnet);
+        assert_eq!(key_version_of(ZPUB).unwrap().standard().prefix, "xpub");
+    }
+
+    #[test]
+    fn should_throw_error_if_incompatible_version() {
+        assert_eq!(
+            convert(ZPUB, KeyVersion::from_prefix("zprv").unwrap()),
+            Err(Bip32Error::IncompatibleVersion("zprv".to_string())),
+        );
+        assert_eq!(
+            convert(ZPUB, KeyVersion::from_prefix("vpub").unwrap()),
+            Err(Bip32Error::IncompatibleVersion("vpub".to_string())),
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use secp256k1::{ecdsa, schnorr, KeyPair, Message, Secp256k1, SecretKey, XOnlyPublicKey};
+use std::str::FromStr;
+
+use crate::address::{Address, AddressError, AddressType};
+use crate::key::{Key, PrivateKey, PublicKey};
+
+mod transaction;
+pub use transaction::{deserialize_witness, serialize_witness, Transaction, TxIn, TxOut, SIGHASH_ALL, SIGHASH_DEFAULT};
+
+#[derive(Debug, PartialEq)]
+pub enum Bip322Error {
+    InvalidBase64(base64::DecodeError),
+    InvalidAddress(AddressError),
+    UnsupportedAddress(AddressType),
+    InvalidTransaction(String),
+    InvalidWitness,
+    UnsupportedSighash(u8),
+    InvalidKey(secp256k1::Error),
+}
+
+impl From<base64::DecodeError> for Bip322Error {
+    fn from(err: base64::DecodeError) -> Self {
+        Bip322Error::InvalidBase64(err)
+    }
+}
+
+impl From<AddressError> for Bip322Error {
+    fn from(err: AddressError) -> Self {
+        Bip322Error::InvalidAddress(err)
+    }
+}
+
+impl From<secp256k1::Error> for Bip322Error {
+    fn from(err: secp256k1::Error) -> Self {
+        Bip322Error::InvalidKey(err)
+    }
+}
+
+/// How much of the `to_sign` transaction a signature carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureFormat {
+    /// Only the witness stack of the `to_sign` input.
+    Simple,
+    /// The whole serialized `to_sign` transaction.
+    Full,
+}
+
+/// Returns `tagged_hash("BIP0322-signed-message", message)`.
+pub fn message_hash(message: &str) -> Vec<u8> {
+    message.as_bytes().to_vec().tagged_hash("BIP0322-signed-message")
+}
+
+/// Returns the virtual `to_spend` transaction, paying the challenged script from a dummy
+/// input committing to the message.
+///
+/// # Arguments
+///
+/// * `script_pubkey` - The script of the address proving ownership.
+/// * `message` - The signed message.
+pub fn to_spend(script_pubkey: &[u8], message: &str) -> Transaction {
+    let mut script_sig = vec![0x00, 0x20];
+    script_sig.append(&mut message_hash(message));
+
+    Transaction {
+        version: 0,
+        inputs: vec![TxIn {
+            prev_txid: vec![0; 32],
+            prev_vout: 0xffff_ffff,
+            script_sig,
+            sequence: 0,
+            witness: Vec::new(),
+        }],
+        outputs: vec![TxOut {
+            value: 0,
+            script_pubkey: script_pubkey.to_vec(),
+        }],
+        lock_time: 0,
+    }
+}
+
+/// Returns the virtual `to_sign` transaction, spending `to_spend` to an `OP_RETURN` output.
+///
+/// # Arguments
+///
+/// * `to_spend` - The `to_spend` transaction of the address and message.
+/// * `witness` - The witness stack satisfying the challenged script.
+pub fn to_sign(to_spend: &Transaction, witness: Vec<Vec<u8>>) -> Transaction {
+    Transaction {
+        version: 0,
+        inputs: vec![TxIn {
+            prev_txid: to_spend.txid(),
+            prev_vout: 0,
+            script_sig: Vec::new(),
+            sequence: 0,
+            witness,
+        }],
+        outputs: vec![TxOut {
+            value: 0,
+            script_pubkey: vec![0x6a],
+        }],
+        lock_time: 0,
+    }
+}
+
+/// Returns the P2WPKH script code, `OP_DUP OP_HASH160 <pkh> OP_EQUALVERIFY OP_CHECKSIG`.
+fn p2wpkh_script_code(pubkey_hash: &[u8]) -> Vec<u8> {
+    let mut script = vec![0x76, 0xa9, 0x14];
+    script.extend_from_slice(pubkey_hash);
+    script.extend_from_slice(&[0x88, 0xac]);
+
+    script
+}
+
+/// Signs a message for the P2WPKH or P2TR address of a private key, returning the base64
+/// encoded signature along with the address.
+///
+/// ECDSA signatures are deterministic with a low R value, as Bitcoin Core produces them.
+/// Schnorr signatures use fresh auxiliary randomness and `SIGHASH_DEFAULT`.
+///
+/// # Arguments
+///
+/// * `private_key` - The signing key.
+/// * `message` - The message as a string slice.
+/// * `address_type` - `AddressType::P2wpkh` or `AddressType::P2tr`.
+/// * `format` - Whether to return the simple or full signature.
+pub fn sign(
+    private_key: &PrivateKey,
+    message: &str,
+    address_type: AddressType,
+    format: SignatureFormat,
+) -> Result<(String, String), Bip322Error> {
+    let secp = Secp256k1::new();
+    let secret_key = SecretKey::from_slice(&private_key.key)?;
+    let pubkey = PublicKey::from_private_key(private_key.clone());
+
+    let address = match address_type {
+        AddressType::P2wpkh => pubkey.clone().get_p2wpkh_address(),
+        AddressType::P2tr => pubkey.clone().get_p2tr_address(None)?,
+        address_type => return Err(Bip322Error::UnsupportedAddress(address_type)),
+    };
+
+    let spend = to_spend(&Address::from_str(&address)?.script_pubkey(), message);
+    let mut sign = to_sign(&spend, Vec::new());
+
+    let witness = match address_type {
+        AddressType::P2wpkh => {
+            let script_code = p2wpkh_script_code(&pubkey.compressed.clone().hash160());
+            let sighash = Message::from_slice(&sign.segwit_v0_sighash(0, &script_code, 0))?;
+
+            let mut signature = secp.sign_ecdsa_low_r(&sighash, &secret_key).serialize_der().to_vec();
+            signature.push(SIGHASH_ALL);
+
+            vec![signature, pubkey.compressed]
+        }
+        _ => {
+            let mut keypair = KeyPair::from_secret_key(&secp, secret_key);
+            keypair.tweak_add_assign(&secp, &pubkey.x_only().tagged_hash("TapTweak"))?;
+
+            let sighash = sign.taproot_key_spend_sighash(0, &spend.outputs, SIGHASH_DEFAULT);
+            let signature = secp.sign_schnorr(&Message::from_slice(&sighash)?, &keypair);
+
+            vec![signature.as_ref().to_vec()]
+        }
+    };
+
+    let signature = match format {
+        SignatureFormat::Simple => serialize_witness(&witness),
+        SignatureFormat::Full => {
+            sign.inputs[0].witness = witness;
+            sign.serialize()
+        }
+    };
+
+    Ok((base64::encode(signature), address))
+}
+
+/// Decodes a simple or full signature into the `to_sign` transaction it stands for.
+fn decode_to_sign(spend: &Transaction, signature: &str) -> Result<Transaction, Bip322Error> {
+    let bytes = base64::decode(signature.trim())?;
+
+    match deserialize_witness(&bytes) {
+        Ok(witness) if !witness.is_empty() => Ok(to_sign(spend, witness)),
+        _ => Transaction::deserialize(&bytes),
+    }
+}
+
+/// Returns true if the simple or full signature proves ownership of a P2WPKH or P2TR address.
+///
+/// # Arguments
+///
+/// * `address` - The address of the signer.
+/// * `message` - The message as a string slice.
+/// * `signature` - The base64 encoded simple or full signature.
+pub fn verify(address: &str, message: &str, signature: &str) -> Result<bool, Bip322Error> {
+    let address = Address::from_str(address)?;
+    let spend = to_spend(&address.script_pubkey(), message);
+    let sign = decode_to_sign(&spend, signature)?;
+
+    // A full signature must be the expected to_sign transaction, its witness aside
+    let mut expected = sign.clone();
+    expected.inputs.iter_mut().for_each(|input| input.witness.clear());
+    if expected != to_sign(&spend, Vec::new()) {
+        return Ok(false);
+    }
+
+    let secp = Secp256k1::verification_only();
+    let witness = &sign.inputs[0].witness;
+
+    match address.address_type {
+        AddressType::P2wpkh => {
+            let (signature, pubkey) = match witness.as_slice() {
+                [signature, pubkey] if pubkey.len() == 33 => (signature, pubkey),
+                _ => return Err(Bip322Error::InvalidWitness),
+            };
+
+            let (sighash_type, der) = signature.split_last().ok_or(Bip322Error::InvalidWitness)?;
+            if *sighash_type != SIGHASH_ALL {
+                return Err(Bip322Error::UnsupportedSighash(*sighash_type));
+            }
+
+            if pubkey.clone().hash160() != address.payload {
+                return Ok(false);
+            }
+
+            let sighash = sign.segwit_v0_sighash(0, &p2wpkh_script_code(&address.payload), 0);
+
+            let verified = match (ecdsa::Signature::from_der(der), secp256k1::PublicKey::from_slice(pubkey)) {
+                (Ok(signature), Ok(pubkey)) => secp
+                    .verify_ecdsa(&Message::from_slice(&sighash)?, &signature, &pubkey)
+                    .is_ok(),
+                _ => false,
+            };
+
+            Ok(verified)
+        }
+        AddressType::P2tr => {
+            let (signature, sighash_type) = match witness.as_slice() {
+                [signature] if signature.len() == 64 => (&signature[..], SIGHASH_DEFAULT),
+                [signature] if signature.len() == 65 && signature[64] == SIGHASH_ALL => (&signature[..64], SIGHASH_ALL),
+                [signature] if signature.len() == 65 => return Err(Bip322Error::UnsupportedSighash(signature[64])),
+                _ => return Err(Bip322Error::InvalidWitness),
+            };
+
+            let sighash = sign.taproot_key_spend_sighash(0, &spend.outputs, sighash_type);
+            let output_key = XOnlyPublicKey::from_slice(&address.payload)?;
+
+            let verified = match schnorr::Signature::from_slice(signature) {
+                Ok(signature) => secp
+                    .verify_schnorr(&signature, &Message::from_slice(&sighash)?, &output_key)
+                    .is_ok(),
+                Err(_) => false,
+            };
+
+            Ok(verified)
+        }
+        address_type => Err(Bip322Error::UnsupportedAddress(address_type)),
+    }
+}
+
+#[cfg(test)]
+mod bip322_tests {
+    use super::*;
+
+    // BIP322 test vectors
+    const WIF: &str = "L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k";
+    const SEGWIT_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    const TAPROOT_ADDRESS: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+    const EMPTY_SIGNATURE: &str = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+    const HELLO_SIGNATURE: &str = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+    const TAPROOT_SIGNATURE: &str = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+
+    fn txid(tx: &Transaction) -> String {
+        hex::encode(tx.txid().into_iter().rev().collect::<Vec<u8>>())
+    }
+
+    #[test]
+    fn should_return_expected_message_hash() {
+        assert_eq!(
+            hex::encode(message_hash("")),
+            "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+        );
+        assert_eq!(
+            hex::encode(message_hash("Hello World")),
+            "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+        );
+    }
+
+    #[test]
+    fn should_return_expected_virtual_transactions() {
+        let script_pubkey = Address::from_str(SEGWIT_ADDRESS).unwrap().script_pubkey();
+
+        let spend = to_spend(&script_pubkey, "");
+        assert_eq!(txid(&spend), "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7");
+        assert_eq!(txid(&to_sign(&spend, Vec::new())), "1e9654e951a5ba44c8604c4de6c67fd78a27e81dcadcfe1edf638ba3aaebaed6");
+
+        let spend = to_spend(&script_pubkey, "Hello World");
+        assert_eq!(txid(&spend), "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b");
+        assert_eq!(txid(&to_sign(&spend, Vec::new())), "88737ae86f2077145f93cc4b153ae9a1cb8d56afa511988c149c5c8c9d93bddf");
+    }
+
+    #[test]
+    fn should_return_expected_segwit_signature() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+
+        assert_eq!(
+            sign(&private_key, "", AddressType::P2wpkh, SignatureFormat::Simple).unwrap(),
+            (String::from(EMPTY_SIGNATURE), String::from(SEGWIT_ADDRESS))
+        );
+        assert_eq!(
+            sign(&private_key, "Hello World", AddressType::P2wpkh, SignatureFormat::Simple).unwrap().0,
+            HELLO_SIGNATURE
+        );
+    }
+
+    #[test]
+    fn should_verify_test_vectors() {
+        assert!(verify(SEGWIT_ADDRESS, "", EMPTY_SIGNATURE).unwrap());
+        assert!(verify(SEGWIT_ADDRESS, "Hello World", HELLO_SIGNATURE).unwrap());
+        assert!(verify(TAPROOT_ADDRESS, "Hello World", TAPROOT_SIGNATURE).unwrap());
+
+        assert!(!verify(SEGWIT_ADDRESS, "Hello World", EMPTY_SIGNATURE).unwrap());
+        assert!(!verify(TAPROOT_ADDRESS, "", TAPROOT_SIGNATURE).unwrap());
+    }
+
+    #[test]
+    fn should_verify_full_and_taproot_signatures() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+
+        for address_type in [AddressType::P2wpkh, AddressType::P2tr] {
+            for format in [SignatureFormat::Simple, SignatureFormat::Full] {
+                let (signature, address) = sign(&private_key, "Hello World", address_type, format).unwrap();
+
+                assert!(verify(&address, "Hello World", &signature).unwrap());
+                assert!(!verify(&address, "Hello World!", &signature).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn should_throw_error_if_unsupported_address() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+
+        assert_eq!(
+            sign(&private_key, "", AddressType::P2pkh, SignatureFormat::Simple),
+            Err(Bip322Error::UnsupportedAddress(AddressType::P2pkh))
+        );
+        assert_eq!(
+            verify("1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy", "", EMPTY_SIGNATURE),
+            Err(Bip322Error::UnsupportedAddress(AddressType::P2pkh))
+        );
+    }
+}
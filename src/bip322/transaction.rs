@@ -0,0 +1,346 @@
+use crate::bip322::Bip322Error;
+use crate::key::Key;
+use crate::utils::compact_size;
+
+/// Signs all inputs and outputs, the only sighash type supported here.
+pub const SIGHASH_ALL: u8 = 0x01;
+
+/// BIP341 default sighash type, same commitment as `SIGHASH_ALL` with a 64-byte signature.
+pub const SIGHASH_DEFAULT: u8 = 0x00;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxIn {
+    /// Txid of the spent output, in internal (little endian) byte order.
+    pub prev_txid: Vec<u8>,
+    pub prev_vout: u32,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+    pub witness: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxOut {
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+/// A minimal Bitcoin transaction, enough to build and check BIP322 virtual transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub version: i32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub lock_time: u32,
+}
+
+fn push_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.append(&mut compact_size(bytes.len() as u64));
+    data.extend_from_slice(bytes);
+}
+
+/// Returns the serialization of a witness stack: item count, then each item length prefixed.
+pub fn serialize_witness(witness: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = compact_size(witness.len() as u64);
+
+    for item in witness {
+        push_bytes(&mut data, item);
+    }
+
+    data
+}
+
+/// Parses a witness stack, failing unless it spans all of `bytes`.
+pub fn deserialize_witness(bytes: &[u8]) -> Result<Vec<Vec<u8>>, Bip322Error> {
+    let mut reader = Reader { bytes, position: 0 };
+    let witness = reader.witness()?;
+    reader.finish()?;
+
+    Ok(witness)
+}
+
+impl Transaction {
+    fn serialize_with(&self, with_witness: bool) -> Vec<u8> {
+        let mut data = self.version.to_le_bytes().to_vec();
+
+        if with_witness {
+            data.extend_from_slice(&[0x00, 0x01]);
+        }
+
+        data.append(&mut compact_size(self.inputs.len() as u64));
+        for input in &self.inputs {
+            data.extend_from_slice(&input.prev_txid);
+            data.extend_from_slice(&input.prev_vout.to_le_bytes());
+            push_bytes(&mut data, &input.script_sig);
+            data.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+
+        data.append(&mut compact_size(self.outputs.len() as u64));
+        for output in &self.outputs {
+            data.extend_from_slice(&output.value.to_le_bytes());
+            push_bytes(&mut data, &output.script_pubkey);
+        }
+
+        if with_witness {
+            for input in &self.inputs {
+                data.append(&mut serialize_witness(&input.witness));
+            }
+        }
+
+        data.extend_from_slice(&self.lock_time.to_le_bytes());
+
+        data
+    }
+
+    /// Returns the network serialization, in the segwit format if any input has a witness.
+    pub fn serialize(&self) -> Vec<u8> {
+        let has_witness = self.inputs.iter().any(|input| !input.witness.is_empty());
+
+        self.serialize_with(has_witness)
+    }
+
+    /// Returns the txid in internal byte order, the double SHA256 of the non witness
+    /// serialization.
+    pub fn txid(&self) -> Vec<u8> {
+        self.serialize_with(false).sha256().sha256()
+    }
+
+    /// Parses a serialized transaction, with or without witnesses.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Bip322Error> {
+        let mut reader = Reader { bytes, position: 0 };
+
+        let version = i32::from_le_bytes(reader.array()?);
+
+        let mut input_count = reader.compact_size()?;
+        let segwit = input_count == 0;
+        if segwit {
+            if reader.take(1)? != [0x01] {
+                return Err(Bip322Error::InvalidTransaction(String::from("invalid segwit flag")));
+            }
+            input_count = reader.compact_size()?;
+        }
+
+        let mut inputs = Vec::new();
+        for _ in 0..input_count {
+            inputs.push(TxIn {
+                prev_txid: reader.take(32)?.to_vec(),
+                prev_vout: u32::from_le_bytes(reader.array()?),
+                script_sig: reader.bytes()?,
+                sequence: u32::from_le_bytes(reader.array()?),
+                witness: Vec::new(),
+            });
+        }
+
+        let mut outputs = Vec::new();
+        for _ in 0..reader.compact_size()? {
+            outputs.push(TxOut {
+                value: u64::from_le_bytes(reader.array()?),
+                script_pubkey: reader.bytes()?,
+            });
+        }
+
+        if segwit {
+            for input in inputs.iter_mut() {
+                input.witness = reader.witness()?;
+            }
+        }
+
+        let lock_time = u32::from_le_bytes(reader.array()?);
+        reader.finish()?;
+
+        Ok(Transaction {
+            version,
+            inputs,
+            outputs,
+            lock_time,
+        })
+    }
+
+    /// Returns the BIP143 signature hash of a segwit v0 input signed with `SIGHASH_ALL`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The input being signed.
+    /// * `script_code` - The script of the spent output, `OP_DUP OP_HASH160 <pkh> OP_EQUALVERIFY OP_CHECKSIG` for P2WPKH.
+    /// * `value` - The amount of the spent output, in satoshis.
+    pub fn segwit_v0_sighash(&self, index: usize, script_code: &[u8], value: u64) -> Vec<u8> {
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &self.inputs {
+            prevouts.extend_from_slice(&input.prev_txid);
+            prevouts.extend_from_slice(&input.prev_vout.to_le_bytes());
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+
+        let mut outputs = Vec::new();
+        for output in &self.outputs {
+            outputs.extend_from_slice(&output.value.to_le_bytes());
+            push_bytes(&mut outputs, &output.script_pubkey);
+        }
+
+        let input = &self.inputs[index];
+
+        let mut preimage = self.version.to_le_bytes().to_vec();
+        preimage.append(&mut prevouts.sha256().sha256());
+        preimage.append(&mut sequences.sha256().sha256());
+        preimage.extend_from_slice(&input.prev_txid);
+        preimage.extend_from_slice(&input.prev_vout.to_le_bytes());
+        push_bytes(&mut preimage, script_code);
+        preimage.extend_from_slice(&value.to_le_bytes());
+        preimage.extend_from_slice(&input.sequence.to_le_bytes());
+        preimage.append(&mut outputs.sha256().sha256());
+        preimage.extend_from_slice(&self.lock_time.to_le_bytes());
+        preimage.extend_from_slice(&(SIGHASH_ALL as u32).to_le_bytes());
+
+        preimage.sha256().sha256()
+    }
+
+    /// Returns the BIP341 signature hash of a taproot key path input.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The input being signed.
+    /// * `spent_outputs` - The outputs spent by every input, in order.
+    /// * `sighash_type` - `SIGHASH_DEFAULT` or `SIGHASH_ALL`.
+    pub fn taproot_key_spend_sighash(&self, index: usize, spent_outputs: &[TxOut], sighash_type: u8) -> Vec<u8> {
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &self.inputs {
+            prevouts.extend_from_slice(&input.prev_txid);
+            prevouts.extend_from_slice(&input.prev_vout.to_le_bytes());
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+
+        let mut amounts = Vec::new();
+        let mut script_pubkeys = Vec::new();
+        for output in spent_outputs {
+            amounts.extend_from_slice(&output.value.to_le_bytes());
+            push_bytes(&mut script_pubkeys, &output.script_pubkey);
+        }
+
+        let mut outputs = Vec::new();
+        for output in &self.outputs {
+            outputs.extend_from_slice(&output.value.to_le_bytes());
+            push_bytes(&mut outputs, &output.script_pubkey);
+        }
+
+        // Epoch 0, then the hash type
+        let mut message = vec![0x00, sighash_type];
+        message.extend_from_slice(&self.version.to_le_bytes());
+        message.extend_from_slice(&self.lock_time.to_le_bytes());
+        message.append(&mut prevouts.sha256());
+        message.append(&mut amounts.sha256());
+        message.append(&mut script_pubkeys.sha256());
+        message.append(&mut sequences.sha256());
+        message.append(&mut outputs.sha256());
+        // Key path spend without annex
+        message.push(0x00);
+        message.extend_from_slice(&(index as u32).to_le_bytes());
+
+        message.tagged_hash("TapSighash")
+    }
+}
+
+/// Reads the fields of a serialized transaction in order.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Bip322Error> {
+        let end = self.position.checked_add(n).filter(|end| *end <= self.bytes.len());
+
+        match end {
+            Some(end) => {
+                let slice = &self.bytes[self.position..end];
+                self.position = end;
+                Ok(slice)
+            }
+            None => Err(Bip322Error::InvalidTransaction(String::from("unexpected end of data"))),
+        }
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Bip322Error> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+
+        Ok(array)
+    }
+
+    fn compact_size(&mut self) -> Result<u64, Bip322Error> {
+        Ok(match self.take(1)?[0] {
+            0xfd => u16::from_le_bytes(self.array()?) as u64,
+            0xfe => u32::from_le_bytes(self.array()?) as u64,
+            0xff => u64::from_le_bytes(self.array()?),
+            n => n as u64,
+        })
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, Bip322Error> {
+        let len = self.compact_size()?;
+
+        Ok(self.take(len as usize)?.to_vec())
+    }
+
+    fn witness(&mut self) -> Result<Vec<Vec<u8>>, Bip322Error> {
+        (0..self.compact_size()?).map(|_| self.bytes()).collect()
+    }
+
+    fn finish(&self) -> Result<(), Bip322Error> {
+        match self.position == self.bytes.len() {
+            true => Ok(()),
+            false => Err(Bip322Error::InvalidTransaction(String::from("trailing data"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod transaction_tests {
+    use super::*;
+
+    fn transaction() -> Transaction {
+        Transaction {
+            version: 2,
+            inputs: vec![TxIn {
+                prev_txid: vec![0xab; 32],
+                prev_vout: 1,
+                script_sig: vec![],
+                sequence: 0xffff_fffd,
+                witness: vec![vec![0x01, 0x02], vec![]],
+            }],
+            outputs: vec![TxOut {
+                value: 50_000,
+                script_pubkey: vec![0x6a],
+            }],
+            lock_time: 0,
+        }
+    }
+
+    #[test]
+    fn should_round_trip_serialization() {
+        let tx = transaction();
+
+        assert_eq!(Transaction::deserialize(&tx.serialize()).unwrap(), tx);
+
+        let mut legacy = transaction();
+        legacy.inputs[0].witness.clear();
+        assert_eq!(Transaction::deserialize(&legacy.serialize()).unwrap(), legacy);
+        assert_eq!(legacy.txid(), tx.txid());
+    }
+
+    #[test]
+    fn should_throw_error_if_truncated_or_trailing_data() {
+        let data = transaction().serialize();
+
+        assert!(Transaction::deserialize(&data[..data.len() - 1]).is_err());
+        assert!(Transaction::deserialize(&[&data[..], &[0x00]].concat()).is_err());
+    }
+
+    #[test]
+    fn should_round_trip_witness() {
+        let witness = vec![vec![0xff; 300], vec![]];
+
+        assert_eq!(deserialize_witness(&serialize_witness(&witness)).unwrap(), witness);
+        assert!(deserialize_witness(&[0x01, 0x02, 0x00]).is_err());
+    }
+}
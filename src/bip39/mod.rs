@@ -0,0 +1,355 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crypto::{hmac::Hmac, pbkdf2::pbkdf2, sha2::Sha512};
+use secp256k1::rand::{self, RngCore};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::key::Key;
+
+#[derive(Debug, PartialEq)]
+pub enum MnemonicError {
+    InvalidWordCount(usize),
+    InvalidEntropyLength(usize),
+    UnknownWord(String),
+    InvalidChecksum,
+}
+
+/// The official BIP39 wordlists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+    Korean,
+    Spanish,
+    ChineseSimplified,
+    ChineseTraditional,
+    French,
+    Italian,
+    Czech,
+    Portuguese,
+}
+
+impl Language {
+    /// Returns the 2048 words of the list, indexed by their 11-bit value.
+    pub fn words(&self) -> Vec<&'static str> {
+        let list = match self {
+            Language::English => include_str!("wordlists/english.txt"),
+            Language::Japanese => include_str!("wordlists/japanese.txt"),
+            Language::Korean => include_str!("wordlists/korean.txt"),
+            Language::Spanish => include_str!("wordlists/spanish.txt"),
+            Language::ChineseSimplified => include_str!("wordlists/chinese_simplified.txt"),
+            Language::ChineseTraditional => include_str!("wordlists/chinese_traditional.txt"),
+            Language::French => include_str!("wordlists/french.txt"),
+            Language::Italian => include_str!("wordlists/italian.txt"),
+            Language::Czech => include_str!("wordlists/czech.txt"),
+            Language::Portuguese => include_str!("wordlists/portuguese.txt"),
+        };
+
+        list.lines().collect()
+    }
+
+    /// Returns the separator used between words when writing a phrase.
+    fn separator(&self) -> &'static str {
+        match self {
+            Language::Japanese => "\u{3000}",
+            _ => " ",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "english" | "en" => Ok(Language::English),
+            "japanese" | "ja" => Ok(Language::Japanese),
+            "korean" | "ko" => Ok(Language::Korean),
+            "spanish" | "es" => Ok(Language::Spanish),
+            "chinese_simplified" | "zh_hans" => Ok(Language::ChineseSimplified),
+            "chinese_traditional" | "zh_hant" => Ok(Language::ChineseTraditional),
+            "french" | "fr" => Ok(Language::French),
+            "italian" | "it" => Ok(Language::Italian),
+            "czech" | "cs" => Ok(Language::Czech),
+            "portuguese" | "pt" => Ok(Language::Portuguese),
+            _ => Err(format!("unknown language: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Language::English => "english",
+            Language::Japanese => "japanese",
+            Language::Korean => "korean",
+            Language::Spanish => "spanish",
+            Language::ChineseSimplified => "chinese_simplified",
+            Language::ChineseTraditional => "chinese_traditional",
+            Language::French => "french",
+            Language::Italian => "italian",
+            Language::Czech => "czech",
+            Language::Portuguese => "portuguese",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A BIP39 mnemonic phrase
+///
+/// Each word encodes 11 bits: the entropy followed by a checksum made of the first
+/// `entropy bits / 32` bits of its SHA256.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mnemonic {
+    pub language: Language,
+    /// Index of each word in the wordlist.
+    pub indices: Vec<u16>,
+}
+
+impl Mnemonic {
+    /// Returns a new mnemonic of `word_count` words from secure random entropy.
+    ///
+    /// # Arguments
+    ///
+    /// * `word_count` - 12, 15, 18, 21 or 24.
+    /// * `language` - The wordlist to pick words from.
+    pub fn generate(word_count: usize, language: Language) -> Result<Self, MnemonicError> {
+        if ![12, 15, 18, 21, 24].contains(&word_count) {
+            return Err(MnemonicError::InvalidWordCount(word_count));
+        }
+
+        let mut entropy = vec![0x00; word_count * 4 / 3];
+        rand::thread_rng().fill_bytes(&mut entropy);
+
+        Mnemonic::from_entropy(&entropy, language)
+    }
+
+    /// Returns the mnemonic encoding the given entropy.
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy` - 16, 20, 24, 28 or 32 bytes of entropy.
+    /// * `language` - The wordlist to pick words from.
+    pub fn from_entropy(entropy: &[u8], language: Language) -> Result<Self, MnemonicError> {
+        if ![16, 20, 24, 28, 32].contains(&entropy.len()) {
+            return Err(MnemonicError::InvalidEntropyLength(entropy.len()));
+        }
+
+        let checksum = entropy.to_vec().sha256()[0];
+        let checksum_bits = entropy.len() / 4;
+
+        let bits: Vec<bool> = entropy
+            .iter()
+            .chain(std::iter::once(&checksum))
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+            .take(entropy.len() * 8 + checksum_bits)
+            .collect();
+
+        let indices = bits
+            .chunks(11)
+            .map(|chunk| chunk.iter().fold(0, |acc, bit| (acc << 1) | *bit as u16))
+            .collect();
+
+        Ok(Mnemonic { language, indices })
+    }
+
+    /// Parses a mnemonic phrase, verifying its words and checksum.
+    ///
+    /// # Arguments
+    ///
+    /// * `phrase` - The words separated by whitespace.
+    /// * `language` - The wordlist the words belong to.
+    pub fn from_phrase(phrase: &str, language: Language) -> Result<Self, MnemonicError> {
+        let words = language.words();
+        let phrase: String = phrase.nfkd().collect();
+
+        let indices = phrase
+            .split_whitespace()
+            .map(|word| {
+                words
+                    .iter()
+                    .position(|w| *w == word)
+                    .map(|i| i as u16)
+                    .ok_or_else(|| MnemonicError::UnknownWord(word.to_string()))
+            })
+            .collect::<Result<Vec<u16>, MnemonicError>>()?;
+
+        if ![12, 15, 18, 21, 24].contains(&indices.len()) {
+            return Err(MnemonicError::InvalidWordCount(indices.len()));
+        }
+
+        let mnemonic = Mnemonic { language, indices };
+
+        match Mnemonic::from_entropy(&mnemonic.entropy(), language) == Ok(mnemonic.clone()) {
+            true => Ok(mnemonic),
+            false => Err(MnemonicError::InvalidChecksum),
+        }
+    }
+
+    /// Returns the entropy encoded by the words, without the checksum.
+    pub fn entropy(&self) -> Vec<u8> {
+        let bits: Vec<bool> = self
+            .indices
+            .iter()
+            .flat_map(|index| (0..11).rev().map(move |i| (index >> i) & 1 == 1))
+            .collect();
+
+        let entropy_bits = bits.len() * 32 / 33;
+
+        bits[..entropy_bits]
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0, |acc, bit| (acc << 1) | *bit as u8))
+            .collect()
+    }
+
+    /// Returns the words of the mnemonic.
+    pub fn words(&self) -> Vec<&'static str> {
+        let words = self.language.words();
+
+        self.indices.iter().map(|i| words[*i as usize]).collect()
+    }
+
+    /// Returns the mnemonic as a phrase.
+    pub fn phrase(&self) -> String {
+        self.words().join(self.language.separator())
+    }
+
+    /// Returns the 64-byte seed, PBKDF2-HMAC-SHA512 of the phrase salted with "mnemonic" and
+    /// the passphrase, over 2048 iterations.
+    ///
+    /// # Arguments
+    ///
+    /// * `passphrase` - The optional passphrase, empty if none.
+    pub fn to_seed(&self, passphrase: &str) -> Vec<u8> {
+        let phrase: String = self.phrase().nfkd().collect();
+        let salt: String = format!("mnemonic{}", passphrase).nfkd().collect();
+
+        let mut mac = Hmac::new(Sha512::new(), phrase.as_bytes());
+        let mut seed = vec![0x00; 64];
+        pbkdf2(&mut mac, salt.as_bytes(), 2048, &mut seed);
+
+        seed
+    }
+}
+
+#[cfg(test)]
+mod bip39_tests {
+    use super::*;
+
+    /// Test vectors from BIP39, all using the passphrase "TREZOR".
+    const VECTORS: [(&str, &str, &str); 4] = [
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "808080808080808080808080808080808080808080808080",
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter always",
+            "107d7c02a5aa6f38c58083ff74f04c607c2d2c0ecc55501dadd72d025b751bc27fe913ffb796f841c49b1d33b610cf0e91d3aa239027f5e99fe4ce9e5088cd65",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+            "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+        ),
+    ];
+
+    #[test]
+    fn should_encode_entropy_as_expected_phrase() {
+        for (entropy, phrase, _) in VECTORS {
+            let mnemonic = Mnemonic::from_entropy(&hex::decode(entropy).unwrap(), Language::English).unwrap();
+
+            assert_eq!(mnemonic.phrase(), phrase);
+        }
+    }
+
+    #[test]
+    fn should_decode_phrase_as_expected_entropy() {
+        for (entropy, phrase, _) in VECTORS {
+            let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+
+            assert_eq!(hex::encode(mnemonic.entropy()), entropy);
+        }
+    }
+
+    #[test]
+    fn should_derive_expected_seed() {
+        for (_, phrase, seed) in VECTORS {
+            let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+
+            assert_eq!(hex::encode(mnemonic.to_seed("TREZOR")), seed);
+        }
+    }
+
+    #[test]
+    fn should_generate_valid_mnemonics() {
+        for word_count in [12, 15, 18, 21, 24] {
+            let mnemonic = Mnemonic::generate(word_count, Language::Spanish).unwrap();
+
+            assert_eq!(mnemonic.words().len(), word_count);
+            assert_eq!(
+                Mnemonic::from_phrase(&mnemonic.phrase(), Language::Spanish),
+                Ok(mnemonic),
+            );
+        }
+    }
+
+    #[test]
+    fn should_join_japanese_words_with_ideographic_space() {
+        let mnemonic = Mnemonic::from_entropy(&[0x00; 16], Language::Japanese).unwrap();
+
+        assert_eq!(mnemonic.phrase().matches('\u{3000}').count(), 11);
+        assert_eq!(
+            Mnemonic::from_phrase(&mnemonic.phrase(), Language::Japanese),
+            Ok(mnemonic),
+        );
+    }
+
+    #[test]
+    fn should_ship_complete_wordlists() {
+        for language in ["english", "japanese", "korean", "spanish", "chinese_simplified",
+                         "chinese_traditional", "french", "italian", "czech", "portuguese"] {
+            assert_eq!(Language::from_str(language).unwrap().words().len(), 2048);
+        }
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_checksum() {
+        assert_eq!(
+            Mnemonic::from_phrase(
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+                Language::English,
+            ),
+            Err(MnemonicError::InvalidChecksum),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_unknown_word() {
+        assert_eq!(
+            Mnemonic::from_phrase("abandon bitcoin", Language::English),
+            Err(MnemonicError::UnknownWord("bitcoin".to_string())),
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_word_count() {
+        assert_eq!(
+            Mnemonic::from_phrase("abandon about", Language::English),
+            Err(MnemonicError::InvalidWordCount(2)),
+        );
+        assert_eq!(
+            Mnemonic::generate(13, Language::English),
+            Err(MnemonicError::InvalidWordCount(13)),
+        );
+    }
+}
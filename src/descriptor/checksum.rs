@@ -0,0 +1,83 @@
+/// Characters allowed in descriptors, grouped so that case errors and common substitutions
+/// are caught by the checksum.
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+/// The characters of the checksum, the same as bech32.
+const CHECKSUM_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const GENERATOR: [u64; 5] = [0xf5dee51989, 0xa9fdca3312, 0x1bab10e32d, 0x3706b1677a, 0x644d626ffd];
+
+fn polymod(symbols: &[u64]) -> u64 {
+    let mut chk: u64 = 1;
+
+    for value in symbols {
+        let top = chk >> 35;
+        chk = (chk & 0x7ffffffff) << 5 ^ value;
+
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+
+    chk
+}
+
+/// Returns the 8-character BIP380 checksum of a descriptor
+///
+/// # Arguments
+///
+/// * `descriptor`: The descriptor, without `#` and checksum
+///
+/// # Return
+///
+/// * The checksum, or the first character not allowed in descriptors
+pub fn descriptor_checksum(descriptor: &str) -> Result<String, char> {
+    let mut symbols = Vec::new();
+    let mut groups = Vec::new();
+
+    for c in descriptor.chars() {
+        let value = INPUT_CHARSET.find(c).ok_or(c)? as u64;
+
+        symbols.push(value & 31);
+        groups.push(value >> 5);
+
+        if groups.len() == 3 {
+            symbols.push(groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+
+    match groups.len() {
+        1 => symbols.push(groups[0]),
+        2 => symbols.push(groups[0] * 3 + groups[1]),
+        _ => (),
+    }
+
+    symbols.extend_from_slice(&[0; 8]);
+    let checksum = polymod(&symbols) ^ 1;
+
+    Ok((0..8)
+        .map(|i| CHECKSUM_CHARSET[((checksum >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect())
+}
+
+#[cfg(test)]
+mod checksum_tests {
+    use super::descriptor_checksum;
+
+    #[test]
+    fn should_return_expected_checksum() {
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert_eq!(
+            descriptor_checksum("wpkh([73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*)").unwrap(),
+            "wc3n3van",
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_character() {
+        assert_eq!(descriptor_checksum("pkh(é)"), Err('é'));
+    }
+}
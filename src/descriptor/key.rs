@@ -0,0 +1,260 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bip32::{Bip32Error, DerivationPath, ExtendedPrivateKey, ExtendedPublicKey, HARDENED};
+use crate::descriptor::DescriptorError;
+use crate::key::{PrivateKey, PublicKey, PublicKeyError};
+
+/// How a key is serialized in the output script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    Compressed,
+    Uncompressed,
+    /// BIP340 32-byte key, only allowed in `tr()`.
+    XOnly,
+}
+
+/// The `*` at the end of a ranged extended key path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wildcard {
+    None,
+    Unhardened,
+    Hardened,
+}
+
+/// The `[fingerprint/path]` prefix recording where a key comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyOrigin {
+    pub fingerprint: Vec<u8>,
+    pub path: DerivationPath,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeySource {
+    Single(PublicKey, KeyFormat),
+    Xprv(ExtendedPrivateKey, DerivationPath, Wildcard),
+    Xpub(ExtendedPublicKey, DerivationPath, Wildcard),
+}
+
+/// A key expression of a descriptor (BIP380)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescriptorKey {
+    /// The expression as written, so the descriptor and its checksum round-trip.
+    pub text: String,
+    pub origin: Option<KeyOrigin>,
+    pub source: KeySource,
+}
+
+impl DescriptorKey {
+    /// Returns true if the key ends with a `*` wildcard.
+    pub fn is_ranged(&self) -> bool {
+        match &self.source {
+            KeySource::Single(..) => false,
+            KeySource::Xprv(_, _, wildcard) | KeySource::Xpub(_, _, wildcard) => {
+                *wildcard != Wildcard::None
+            }
+        }
+    }
+
+    /// Returns the format of the key in scripts, derived keys are always compressed.
+    pub fn format(&self) -> KeyFormat {
+        match &self.source {
+            KeySource::Single(_, format) => *format,
+            _ => KeyFormat::Compressed,
+        }
+    }
+
+    /// Returns the public key, derived at `index` if the key is ranged.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The child number replacing the wildcard, below 2^31, ignored by non ranged
+    ///   keys.
+    pub fn public_key_at(&self, index: u32) -> Result<PublicKey, DescriptorError> {
+        if self.is_ranged() && index >= HARDENED {
+            return Err(Bip32Error::IndexOutOfRange(index).into());
+        }
+
+        let child = |path: &DerivationPath, wildcard: &Wildcard| match wildcard {
+            Wildcard::None => path.clone(),
+            Wildcard::Unhardened => path.child(index),
+            Wildcard::Hardened => path.child(index | HARDENED),
+        };
+
+        match &self.source {
+            KeySource::Single(pubkey, _) => Ok(pubkey.clone()),
+            KeySource::Xprv(xprv, path, wildcard) => {
+                Ok(xprv.derive_path(&child(path, wildcard))?.public_key())
+            }
+            KeySource::Xpub(xpub, path, wildcard) => {
+                Ok(xpub.derive_path(&child(path, wildcard))?.public_key)
+            }
+        }
+    }
+
+    /// Returns the key as serialized in scripts at `index`.
+    pub fn script_bytes_at(&self, index: u32) -> Result<Vec<u8>, DescriptorError> {
+        let pubkey = self.public_key_at(index)?;
+
+        Ok(match self.format() {
+            KeyFormat::Compressed => pubkey.compressed,
+            KeyFormat::Uncompressed => pubkey.uncompressed,
+            KeyFormat::XOnly => pubkey.x_only(),
+        })
+    }
+}
+
+/// Builds a `PublicKey` from its serialization, x-only keys being lifted to an even y.
+fn parse_public_key(bytes: &[u8]) -> Result<PublicKey, PublicKeyError> {
+    match bytes.len() {
+        32 => PublicKey::from_x_only(bytes, false),
+        _ => PublicKey::from_slice(bytes),
+    }
+}
+
+/// Parses the steps of a descriptor path such as `/0/*`, returning the path and wildcard.
+fn parse_path(steps: &[&str], text: &str) -> Result<(DerivationPath, Wildcard), DescriptorError> {
+    let invalid = || DescriptorError::InvalidKey(text.to_string());
+
+    let (wildcard, steps) = match steps.split_last() {
+        Some((&"*", rest)) => (Wildcard::Unhardened, rest),
+        Some((&"*'", rest)) | Some((&"*h", rest)) | Some((&"*H", rest)) => (Wildcard::Hardened, rest),
+        _ => (Wildcard::None, steps),
+    };
+
+    let path = if steps.is_empty() {
+        DerivationPath::default()
+    } else {
+        DerivationPath::from_str(&format!("m/{}", steps.join("/"))).map_err(|_| invalid())?
+    };
+
+    Ok((path, wildcard))
+}
+
+impl FromStr for DescriptorKey {
+    type Err = DescriptorError;
+
+    /// Parses a key expression: an optional `[fingerprint/path]` origin followed by a hex public
+    /// key, a WIF private key or an extended key with derivation steps and wildcard.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || DescriptorError::InvalidKey(text.to_string());
+
+        let (origin, key) = match text.strip_prefix('[') {
+            Some(rest) => {
+                let (origin, key) = rest.split_once(']').ok_or_else(invalid)?;
+                let (fingerprint, path) = match origin.split_once('/') {
+                    Some((fingerprint, path)) => (fingerprint, format!("m/{}", path)),
+                    None => (origin, String::from("m")),
+                };
+
+                if fingerprint.len() != 8 {
+                    return Err(invalid());
+                }
+
+                let origin = KeyOrigin {
+                    fingerprint: hex::decode(fingerprint).map_err(|_| invalid())?,
+                    path: DerivationPath::from_str(&path).map_err(|_| invalid())?,
+                };
+
+                (Some(origin), key)
+            }
+            None => (None, text),
+        };
+
+        let source = if key.chars().all(|c| c.is_ascii_hexdigit()) {
+            let bytes = hex::decode(key).map_err(|_| invalid())?;
+            let format = match (bytes.len(), bytes.first()) {
+                (33, Some(0x02)) | (33, Some(0x03)) => KeyFormat::Compressed,
+                (65, Some(0x04)) => KeyFormat::Uncompressed,
+                (32, _) => KeyFormat::XOnly,
+                _ => return Err(invalid()),
+            };
+
+            KeySource::Single(parse_public_key(&bytes).map_err(|_| invalid())?, format)
+        } else {
+            let parts: Vec<&str> = key.split('/').collect();
+
+            if let Ok(xprv) = ExtendedPrivateKey::from_base58check(parts[0]) {
+                let (path, wildcard) = parse_path(&parts[1..], text)?;
+                KeySource::Xprv(xprv, path, wildcard)
+            } else if let Ok(xpub) = ExtendedPublicKey::from_base58check(parts[0]) {
+                let (path, wildcard) = parse_path(&parts[1..], text)?;
+
+                if wildcard == Wildcard::Hardened || path.steps.iter().any(|step| step >= &HARDENED) {
+                    return Err(DescriptorError::InvalidKey(format!(
+                        "{}: hardened derivation requires a private key",
+                        text
+                    )));
+                }
+
+                KeySource::Xpub(xpub, path, wildcard)
+            } else if parts.len() == 1 {
+                let private_key = PrivateKey::from_wif(key).map_err(|_| invalid())?;
+                let format = if private_key.compressed == Some(true) {
+                    KeyFormat::Compressed
+                } else {
+                    KeyFormat::Uncompressed
+                };
+
+                KeySource::Single(PublicKey::from_private_key(private_key), format)
+            } else {
+                return Err(invalid());
+            }
+        };
+
+        Ok(DescriptorKey {
+            text: text.to_string(),
+            origin,
+            source,
+        })
+    }
+}
+
+impl fmt::Display for DescriptorKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod descriptor_key_tests {
+    use super::*;
+    use crate::key::COMPRESSED_PUBLIC_KEY;
+
+    #[test]
+    fn should_parse_key_origin() {
+        let key = DescriptorKey::from_str(&format!("[d34db33f/44'/0'/0']{}", COMPRESSED_PUBLIC_KEY)).unwrap();
+        let origin = key.origin.unwrap();
+
+        assert_eq!(origin.fingerprint, hex::decode("d34db33f").unwrap());
+        assert_eq!(origin.path.to_string(), "m/44'/0'/0'");
+        assert_eq!(key.source, KeySource::Single(parse_public_key(&hex::decode(COMPRESSED_PUBLIC_KEY).unwrap()).unwrap(), KeyFormat::Compressed));
+    }
+
+    #[test]
+    fn should_parse_ranged_extended_key() {
+        let key = DescriptorKey::from_str("xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*").unwrap();
+
+        assert!(key.is_ranged());
+        assert_eq!(
+            key.public_key_at(0).unwrap().get_p2wpkh_address(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+        assert_eq!(
+            key.public_key_at(HARDENED),
+            Err(DescriptorError::Bip32(Bip32Error::IndexOutOfRange(HARDENED)))
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_hardened_step_from_xpub() {
+        assert!(DescriptorKey::from_str("xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*'").is_err());
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_key() {
+        assert!(DescriptorKey::from_str("02deadbeef").is_err());
+        assert!(DescriptorKey::from_str("[d34db33f/44'/0'/0'").is_err());
+        assert!(DescriptorKey::from_str("[d34db3/44']03f028892bad7ed57d2fb57bf33081d5cfcf6f9ed3d3d7f159c2e2fff579dc341a").is_err());
+    }
+}
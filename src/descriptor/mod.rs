@@ -0,0 +1,503 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::base58encoder::base58check_encode;
+use crate::bech32;
+use crate::bip32::Bip32Error;
+use crate::key::{Key, PublicKey};
+use crate::network::Network;
+
+mod checksum;
+pub use checksum::descriptor_checksum;
+
+mod key;
+pub use key::{DescriptorKey, KeyFormat, KeyOrigin, KeySource, Wildcard};
+
+#[derive(Debug, PartialEq)]
+pub enum DescriptorError {
+    InvalidChecksum { expected: String, found: String },
+    InvalidCharacter(char),
+    Syntax(String),
+    InvalidKey(String),
+    Unsupported(String),
+    InvalidThreshold { threshold: usize, keys: usize },
+    TooManyKeys(usize),
+    /// Bare `multi()` outputs have a script but no address.
+    NoAddress,
+    Bip32(Bip32Error),
+}
+
+impl From<Bip32Error> for DescriptorError {
+    fn from(err: Bip32Error) -> Self {
+        DescriptorError::Bip32(err)
+    }
+}
+
+/// The `k` of `n` keys of a `multi()` or `sortedmulti()` expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Multi {
+    pub threshold: usize,
+    pub keys: Vec<DescriptorKey>,
+    /// `sortedmulti()` orders the keys lexicographically in the script.
+    pub sorted: bool,
+}
+
+impl Multi {
+    /// Returns the `OP_k <keys> OP_n OP_CHECKMULTISIG` script at `index`.
+    pub fn script_at(&self, index: u32) -> Result<Vec<u8>, DescriptorError> {
+        let mut keys = self
+            .keys
+            .iter()
+            .map(|key| key.script_bytes_at(index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.sorted {
+            keys.sort();
+        }
+
+        let mut script = push_int(self.threshold);
+        for key in keys {
+            script.push(key.len() as u8);
+            script.extend(key);
+        }
+        script.append(&mut push_int(self.keys.len()));
+        script.push(0xae);
+
+        Ok(script)
+    }
+}
+
+impl fmt::Display for Multi {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = if self.sorted { "sortedmulti" } else { "multi" };
+        let keys: Vec<String> = self.keys.iter().map(|key| key.to_string()).collect();
+
+        write!(f, "{}({},{})", name, self.threshold, keys.join(","))
+    }
+}
+
+/// Pushes 1 to 16 with `OP_1` to `OP_16`, larger key counts as a one byte number.
+fn push_int(n: usize) -> Vec<u8> {
+    match n {
+        1..=16 => vec![0x50 + n as u8],
+        _ => vec![0x01, n as u8],
+    }
+}
+
+/// Returns the P2SH scriptPubKey paying to `redeem_script`.
+fn p2sh_script(redeem_script: &[u8]) -> Vec<u8> {
+    let mut script = vec![0xa9, 0x14];
+    script.append(&mut redeem_script.to_vec().hash160());
+    script.push(0x87);
+
+    script
+}
+
+/// Returns the P2WSH scriptPubKey paying to `witness_script`.
+fn p2wsh_script(witness_script: &[u8]) -> Vec<u8> {
+    let mut script = vec![0x00, 0x20];
+    script.append(&mut witness_script.to_vec().sha256());
+
+    script
+}
+
+/// An output script descriptor (BIP380-386)
+///
+/// Supports `pkh()`, `wpkh()`, `sh(wpkh())`, key path only `tr()`, and `multi()` /
+/// `sortedmulti()` bare or nested in `sh()`, `wsh()` and `sh(wsh())`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Descriptor {
+    Pkh(DescriptorKey),
+    Wpkh(DescriptorKey),
+    ShWpkh(DescriptorKey),
+    Tr(DescriptorKey),
+    Multi(Multi),
+    ShMulti(Multi),
+    WshMulti(Multi),
+    ShWshMulti(Multi),
+}
+
+impl Descriptor {
+    /// Returns the keys of the descriptor.
+    pub fn keys(&self) -> Vec<&DescriptorKey> {
+        match self {
+            Descriptor::Pkh(key) | Descriptor::Wpkh(key) | Descriptor::ShWpkh(key) | Descriptor::Tr(key) => {
+                vec![key]
+            }
+            Descriptor::Multi(multi)
+            | Descriptor::ShMulti(multi)
+            | Descriptor::WshMulti(multi)
+            | Descriptor::ShWshMulti(multi) => multi.keys.iter().collect(),
+        }
+    }
+
+    /// Returns true if any key ends with a `*` wildcard.
+    pub fn is_ranged(&self) -> bool {
+        self.keys().iter().any(|key| key.is_ranged())
+    }
+
+    /// Returns the 8-character checksum of the descriptor.
+    pub fn checksum(&self) -> String {
+        descriptor_checksum(&self.to_string()).expect("parsed descriptors only hold valid characters")
+    }
+
+    /// Returns the descriptor followed by `#` and its checksum.
+    pub fn to_string_with_checksum(&self) -> String {
+        format!("{}#{}", self, self.checksum())
+    }
+
+    /// Returns the address of the output at `index`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The child number replacing wildcards, ignored by non ranged descriptors.
+    /// * `network` - The network the address is encoded for.
+    pub fn address_at(&self, index: u32, network: Network) -> Result<String, DescriptorError> {
+        let public_key = |key: &DescriptorKey| -> Result<PublicKey, DescriptorError> {
+            let mut pubkey = key.public_key_at(index)?;
+            pubkey.network = network;
+
+            Ok(pubkey)
+        };
+
+        match self {
+            Descriptor::Pkh(key) => match key.format() {
+                KeyFormat::Uncompressed => Ok(public_key(key)?.get_address_from_uncompressed()),
+                _ => Ok(public_key(key)?.get_address_from_compressed()),
+            },
+            Descriptor::Wpkh(key) => Ok(public_key(key)?.get_p2wpkh_address()),
+            Descriptor::ShWpkh(key) => Ok(public_key(key)?.get_p2sh_p2wpkh_address()),
+            Descriptor::Tr(key) => public_key(key)?
+                .get_p2tr_address(None)
+                .map_err(|err| DescriptorError::InvalidKey(format!("{}: {:?}", key, err))),
+            Descriptor::Multi(_) => Err(DescriptorError::NoAddress),
+            Descriptor::ShMulti(multi) => Ok(base58check_encode(
+                &[network.p2sh_prefix()],
+                &multi.script_at(index)?.hash160(),
+            )),
+            Descriptor::WshMulti(multi) => Ok(bech32::encode_segwit_address(
+                network.bech32_hrp(),
+                0,
+                &multi.script_at(index)?.sha256(),
+            )),
+            Descriptor::ShWshMulti(multi) => Ok(base58check_encode(
+                &[network.p2sh_prefix()],
+                &p2wsh_script(&multi.script_at(index)?).hash160(),
+            )),
+        }
+    }
+
+    /// Returns the scriptPubKey of the output at `index`.
+    pub fn script_pubkey_at(&self, index: u32) -> Result<Vec<u8>, DescriptorError> {
+        match self {
+            Descriptor::Pkh(key) => {
+                let mut script = vec![0x76, 0xa9, 0x14];
+                script.append(&mut key.script_bytes_at(index)?.hash160());
+                script.extend([0x88, 0xac]);
+
+                Ok(script)
+            }
+            Descriptor::Wpkh(key) => Ok(key.public_key_at(index)?.p2wpkh_redeem_script()),
+            Descriptor::ShWpkh(key) => Ok(p2sh_script(&key.public_key_at(index)?.p2wpkh_redeem_script())),
+            Descriptor::Tr(key) => {
+                let mut script = vec![0x51, 0x20];
+                script.append(
+                    &mut key
+                        .public_key_at(index)?
+                        .taproot_output_key(None)
+                        .map_err(|err| DescriptorError::InvalidKey(format!("{}: {:?}", key, err)))?,
+                );
+
+                Ok(script)
+            }
+            Descriptor::Multi(multi) => multi.script_at(index),
+            Descriptor::ShMulti(multi) => Ok(p2sh_script(&multi.script_at(index)?)),
+            Descriptor::WshMulti(multi) => Ok(p2wsh_script(&multi.script_at(index)?)),
+            Descriptor::ShWshMulti(multi) => Ok(p2sh_script(&p2wsh_script(&multi.script_at(index)?))),
+        }
+    }
+}
+
+impl fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Descriptor::Pkh(key) => write!(f, "pkh({})", key),
+            Descriptor::Wpkh(key) => write!(f, "wpkh({})", key),
+            Descriptor::ShWpkh(key) => write!(f, "sh(wpkh({}))", key),
+            Descriptor::Tr(key) => write!(f, "tr({})", key),
+            Descriptor::Multi(multi) => write!(f, "{}", multi),
+            Descriptor::ShMulti(multi) => write!(f, "sh({})", multi),
+            Descriptor::WshMulti(multi) => write!(f, "wsh({})", multi),
+            Descriptor::ShWshMulti(multi) => write!(f, "sh(wsh({}))", multi),
+        }
+    }
+}
+
+/// Where a script expression appears, restricting which keys and how many of them are allowed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Context {
+    Top,
+    Sh,
+    Wsh,
+    Tr,
+}
+
+/// Splits `name(args)` into its name and arguments.
+fn split_call(expression: &str) -> Result<(&str, &str), DescriptorError> {
+    let syntax = || DescriptorError::Syntax(expression.to_string());
+
+    let (name, rest) = expression.split_once('(').ok_or_else(syntax)?;
+    let args = rest.strip_suffix(')').ok_or_else(syntax)?;
+
+    Ok((name, args))
+}
+
+fn parse_key(text: &str, context: Context) -> Result<DescriptorKey, DescriptorError> {
+    let key = DescriptorKey::from_str(text)?;
+
+    match (key.format(), context) {
+        (KeyFormat::XOnly, Context::Tr) | (KeyFormat::Compressed, _) => Ok(key),
+        (KeyFormat::Uncompressed, Context::Top) | (KeyFormat::Uncompressed, Context::Sh) => Ok(key),
+        (KeyFormat::XOnly, _) => Err(DescriptorError::InvalidKey(format!(
+            "{}: x-only keys are only allowed in tr()",
+            text
+        ))),
+        (KeyFormat::Uncompressed, _) => Err(DescriptorError::InvalidKey(format!(
+            "{}: uncompressed keys are not allowed in segwit",
+            text
+        ))),
+    }
+}
+
+fn parse_multi(name: &str, args: &str, context: Context) -> Result<Multi, DescriptorError> {
+    let mut args = args.split(',');
+    let threshold = args.next().unwrap_or_default();
+    let threshold: usize = threshold
+        .parse()
+        .map_err(|_| DescriptorError::Syntax(format!("invalid threshold {}", threshold)))?;
+
+    let keys = args
+        .map(|key| parse_key(key, context))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let max_keys = match context {
+        Context::Top => 3,
+        Context::Sh => 15,
+        _ => 20,
+    };
+
+    if keys.len() > max_keys {
+        return Err(DescriptorError::TooManyKeys(keys.len()));
+    }
+
+    if threshold == 0 || threshold > keys.len() {
+        return Err(DescriptorError::InvalidThreshold {
+            threshold,
+            keys: keys.len(),
+        });
+    }
+
+    Ok(Multi {
+        threshold,
+        keys,
+        sorted: name == "sortedmulti",
+    })
+}
+
+impl FromStr for Descriptor {
+    type Err = DescriptorError;
+
+    /// Parses a descriptor, verifying its checksum if one follows a `#`.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - The descriptor, with or without checksum.
+    fn from_str(descriptor: &str) -> Result<Self, Self::Err> {
+        let (body, found) = match descriptor.split_once('#') {
+            Some((body, found)) => (body, Some(found)),
+            None => (descriptor, None),
+        };
+
+        let expected = descriptor_checksum(body).map_err(DescriptorError::InvalidCharacter)?;
+
+        if let Some(found) = found {
+            if found != expected {
+                return Err(DescriptorError::InvalidChecksum {
+                    expected,
+                    found: found.to_string(),
+                });
+            }
+        }
+
+        let (name, args) = split_call(body)?;
+
+        match name {
+            "pkh" => Ok(Descriptor::Pkh(parse_key(args, Context::Top)?)),
+            "wpkh" => Ok(Descriptor::Wpkh(parse_key(args, Context::Wsh)?)),
+            "tr" if args.contains(',') => Err(DescriptorError::Unsupported(String::from(
+                "tr() script trees",
+            ))),
+            "tr" => Ok(Descriptor::Tr(parse_key(args, Context::Tr)?)),
+            "multi" | "sortedmulti" => Ok(Descriptor::Multi(parse_multi(name, args, Context::Top)?)),
+            "sh" | "wsh" => {
+                let (inner, inner_args) = split_call(args)?;
+
+                match (name, inner) {
+                    ("sh", "wpkh") => Ok(Descriptor::ShWpkh(parse_key(inner_args, Context::Wsh)?)),
+                    ("sh", "multi") | ("sh", "sortedmulti") => {
+                        Ok(Descriptor::ShMulti(parse_multi(inner, inner_args, Context::Sh)?))
+                    }
+                    ("wsh", "multi") | ("wsh", "sortedmulti") => {
+                        Ok(Descriptor::WshMulti(parse_multi(inner, inner_args, Context::Wsh)?))
+                    }
+                    ("sh", "wsh") => {
+                        let (multi, multi_args) = split_call(inner_args)?;
+
+                        match multi {
+                            "multi" | "sortedmulti" => Ok(Descriptor::ShWshMulti(parse_multi(
+                                multi,
+                                multi_args,
+                                Context::Wsh,
+                            )?)),
+                            _ => Err(DescriptorError::Unsupported(format!("sh(wsh({}()))", multi))),
+                        }
+                    }
+                    _ => Err(DescriptorError::Unsupported(format!("{}({}())", name, inner))),
+                }
+            }
+            _ => Err(DescriptorError::Unsupported(format!("{}()", name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod descriptor_tests {
+    use super::*;
+    use crate::key::{COMPRESSED_PUBLIC_KEY, P2SH_P2WPKH_ADDRESS, P2TR_ADDRESS, P2WPKH_ADDRESS, UNCOMPRESSED_PUBLIC_KEY};
+
+    const XPUB_DESCRIPTOR: &str = "wpkh([73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*)";
+
+    fn address(descriptor: &str) -> String {
+        Descriptor::from_str(descriptor)
+            .unwrap()
+            .address_at(0, Network::Mainnet)
+            .unwrap()
+    }
+
+    #[test]
+    fn should_verify_checksum() {
+        let descriptor = format!("{}#wc3n3van", XPUB_DESCRIPTOR);
+
+        assert_eq!(Descriptor::from_str(&descriptor).unwrap().to_string_with_checksum(), descriptor);
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_checksum() {
+        assert_eq!(
+            Descriptor::from_str(&format!("{}#wc3n3vaa", XPUB_DESCRIPTOR)),
+            Err(DescriptorError::InvalidChecksum {
+                expected: String::from("wc3n3van"),
+                found: String::from("wc3n3vaa"),
+            })
+        );
+    }
+
+    #[test]
+    fn should_return_single_key_addresses() {
+        assert_eq!(
+            address(&format!("pkh({})", COMPRESSED_PUBLIC_KEY)),
+            PublicKey::from_private_key_string(crate::key::PRIVATE_KEY).unwrap().get_address_from_compressed()
+        );
+        assert_eq!(address(&format!("wpkh({})", COMPRESSED_PUBLIC_KEY)), P2WPKH_ADDRESS);
+        assert_eq!(address(&format!("sh(wpkh({}))", COMPRESSED_PUBLIC_KEY)), P2SH_P2WPKH_ADDRESS);
+        assert_eq!(address(&format!("tr({})", COMPRESSED_PUBLIC_KEY)), P2TR_ADDRESS);
+        assert_eq!(address(&format!("tr({})", &COMPRESSED_PUBLIC_KEY[2..])), P2TR_ADDRESS);
+    }
+
+    #[test]
+    fn should_derive_ranged_addresses() {
+        let descriptor = Descriptor::from_str(XPUB_DESCRIPTOR).unwrap();
+
+        assert!(descriptor.is_ranged());
+        assert_eq!(
+            descriptor.address_at(0, Network::Mainnet).unwrap(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+    }
+
+    #[test]
+    fn should_return_multi_script() {
+        // BIP383 test vector
+        let descriptor = Descriptor::from_str("multi(1,022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe4,025cbdf0646e5db4eaa398f365f2ea7a0e3d419b7e0330e39ce92bddedcac4f9bc)").unwrap();
+
+        assert_eq!(
+            hex::encode(descriptor.script_pubkey_at(0).unwrap()),
+            "5121022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe421025cbdf0646e5db4eaa398f365f2ea7a0e3d419b7e0330e39ce92bddedcac4f9bc52ae"
+        );
+        assert_eq!(descriptor.address_at(0, Network::Mainnet), Err(DescriptorError::NoAddress));
+    }
+
+    #[test]
+    fn should_return_p2sh_multi_script_from_extended_keys() {
+        // BIP383 test vector
+        let descriptor = Descriptor::from_str("sh(multi(2,[00000000/111'/222]xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc,xprv9uPDJpEQgRQfDcW7BkF7eTya6RPxXeJCqCJGHuCJ4GiRVLzkTXBAJMu2qaMWPrS7AANYqdq6vcBcBUdJCVVFceUvJFjaPdGZ2y9WACViL4L/0))").unwrap();
+
+        assert_eq!(
+            hex::encode(descriptor.script_pubkey_at(0).unwrap()),
+            "a91445a9a622a8b0a1269944be477640eedc447bbd8487"
+        );
+    }
+
+    #[test]
+    fn should_sort_sortedmulti_keys() {
+        let multi = Descriptor::from_str("sh(multi(1,03acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe,022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe4))").unwrap();
+        let sorted = Descriptor::from_str("sh(sortedmulti(1,022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe4,03acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe))").unwrap();
+        let sorted_reversed = Descriptor::from_str("sh(sortedmulti(1,03acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe,022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe4))").unwrap();
+
+        assert_ne!(multi.script_pubkey_at(0), sorted.script_pubkey_at(0));
+        assert_eq!(sorted.script_pubkey_at(0), sorted_reversed.script_pubkey_at(0));
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_descriptor() {
+        assert!(matches!(
+            Descriptor::from_str(&format!("wpkh({})", UNCOMPRESSED_PUBLIC_KEY)),
+            Err(DescriptorError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            Descriptor::from_str(&format!("sh(multi(2,{}))", COMPRESSED_PUBLIC_KEY)),
+            Err(DescriptorError::InvalidThreshold { threshold: 2, keys: 1 })
+        ));
+        assert!(matches!(
+            Descriptor::from_str(&format!("combo({})", COMPRESSED_PUBLIC_KEY)),
+            Err(DescriptorError::Unsupported(_))
+        ));
+        assert!(matches!(
+            Descriptor::from_str(&format!("pkh({}", COMPRESSED_PUBLIC_KEY)),
+            Err(DescriptorError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn should_return_script_pubkey_of_address() {
+        let multi = "multi(1,022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe4,025cbdf0646e5db4eaa398f365f2ea7a0e3d419b7e0330e39ce92bddedcac4f9bc)";
+        let descriptors = [
+            format!("pkh({})", COMPRESSED_PUBLIC_KEY),
+            format!("pkh({})", UNCOMPRESSED_PUBLIC_KEY),
+            format!("wpkh({})", COMPRESSED_PUBLIC_KEY),
+            format!("sh(wpkh({}))", COMPRESSED_PUBLIC_KEY),
+            format!("tr({})", COMPRESSED_PUBLIC_KEY),
+            format!("sh({})", multi),
+            format!("wsh({})", multi),
+            format!("sh(wsh({}))", multi),
+            XPUB_DESCRIPTOR.to_string(),
+        ];
+
+        for descriptor in descriptors {
+            let descriptor = Descriptor::from_str(&descriptor).unwrap();
+            let address = crate::address::Address::from_str(&address(&descriptor.to_string())).unwrap();
+
+            assert_eq!(descriptor.script_pubkey_at(0).unwrap(), address.script_pubkey(), "{}", descriptor);
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use num::{BigUint, Zero};
+use secp256k1::{Message, Secp256k1};
+use std::fmt;
+
+use crate::key::{curve_order, PublicKey};
+
+#[derive(Debug, PartialEq)]
+pub enum SignatureError {
+    /// The signature breaks the BIP66 strict DER rules, for the given reason.
+    InvalidDer(&'static str),
+    InvalidLength(usize),
+    InvalidDigestLength(usize),
+    InvalidSignature(secp256k1::Error),
+}
+
+impl From<secp256k1::Error> for SignatureError {
+    fn from(err: secp256k1::Error) -> Self {
+        SignatureError::InvalidSignature(err)
+    }
+}
+
+/// An ECDSA signature, as its two 32-byte big endian integers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub r: Vec<u8>,
+    pub s: Vec<u8>,
+}
+
+/// Returns the 32-byte value of a DER integer, dropping its sign padding.
+fn der_integer_value(integer: &[u8]) -> Result<Vec<u8>, SignatureError> {
+    let mut value = integer;
+    while value.len() > 1 && value[0] == 0 {
+        value = &value[1..];
+    }
+
+    if value.len() > 32 {
+        return Err(SignatureError::InvalidDer("integer larger than 32 bytes"));
+    }
+
+    let mut padded = vec![0; 32 - value.len()];
+    padded.extend_from_slice(value);
+
+    Ok(padded)
+}
+
+/// Returns the shortest DER integer encoding of a 32-byte value.
+fn der_integer(value: &[u8]) -> Vec<u8> {
+    let start = value.iter().position(|b| *b != 0).unwrap_or(value.len() - 1);
+    let mut integer = value[start..].to_vec();
+
+    if integer[0] & 0x80 != 0 {
+        integer.insert(0, 0x00);
+    }
+
+    integer.insert(0, integer.len() as u8);
+    integer.insert(0, 0x02);
+
+    integer
+}
+
+impl Signature {
+    /// Parses a DER signature following the BIP66 strict encoding rules, without sighash byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `der` - The `0x30 <len> 0x02 <len> <r> 0x02 <len> <s>` encoded signature.
+    pub fn from_der(der: &[u8]) -> Result<Self, SignatureError> {
+        let invalid = |reason| Err(SignatureError::InvalidDer(reason));
+        let len = der.len();
+
+        if !(8..=72).contains(&len) {
+            return invalid("length out of range");
+        }
+        if der[0] != 0x30 {
+            return invalid("missing compound marker");
+        }
+        if der[1] as usize != len - 2 {
+            return invalid("length does not cover the signature");
+        }
+
+        let len_r = der[3] as usize;
+        if 5 + len_r >= len {
+            return invalid("R length overflows the signature");
+        }
+
+        let len_s = der[5 + len_r] as usize;
+        if len_r + len_s + 6 != len {
+            return invalid("R and S lengths do not match the signature length");
+        }
+
+        if der[2] != 0x02 {
+            return invalid("R is not an integer");
+        }
+        if len_r == 0 {
+            return invalid("R is empty");
+        }
+        if der[4] & 0x80 != 0 {
+            return invalid("R is negative");
+        }
+        if len_r > 1 && der[4] == 0x00 && der[5] & 0x80 == 0 {
+            return invalid("R has excessive padding");
+        }
+
+        if der[len_r + 4] != 0x02 {
+            return invalid("S is not an integer");
+        }
+        if len_s == 0 {
+            return invalid("S is empty");
+        }
+        if der[len_r + 6] & 0x80 != 0 {
+            return invalid("S is negative");
+        }
+        if len_s > 1 && der[len_r + 6] == 0x00 && der[len_r + 7] & 0x80 == 0 {
+            return invalid("S has excessive padding");
+        }
+
+        Ok(Signature {
+            r: der_integer_value(&der[4..4 + len_r])?,
+            s: der_integer_value(&der[6 + len_r..])?,
+        })
+    }
+
+    /// Parses a DER signature leniently, as Bitcoin did before BIP66.
+    pub fn from_der_lax(der: &[u8]) -> Result<Self, SignatureError> {
+        let signature = secp256k1::ecdsa::Signature::from_der_lax(der)?;
+
+        Signature::from_compact(&signature.serialize_compact())
+    }
+
+    /// Parses the 64-byte `r || s` compact encoding.
+    pub fn from_compact(compact: &[u8]) -> Result<Self, SignatureError> {
+        if compact.len() != 64 {
+            return Err(SignatureError::InvalidLength(compact.len()));
+        }
+
+        Ok(Signature {
+            r: compact[..32].to_vec(),
+            s: compact[32..].to_vec(),
+        })
+    }
+
+    /// Returns the DER encoding, without sighash byte.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut body = der_integer(&self.r);
+        body.append(&mut der_integer(&self.s));
+
+        let mut der = vec![0x30, body.len() as u8];
+        der.append(&mut body);
+
+        der
+    }
+
+    /// Returns the 64-byte `r || s` compact encoding.
+    pub fn to_compact(&self) -> Vec<u8> {
+        [&self.r[..], &self.s[..]].concat()
+    }
+
+    /// Returns true if `r` and `s` are both in `[1, n - 1]`, n being the curve order.
+    pub fn is_in_range(&self) -> bool {
+        let n = curve_order();
+        let in_range = |scalar: &[u8]| {
+            let scalar = BigUint::from_bytes_be(scalar);
+            !scalar.is_zero() && scalar < n
+        };
+
+        in_range(&self.r) && in_range(&self.s)
+    }
+
+    /// Returns true if `r` and `s` are in range and `s` is at most half the curve order, as
+    /// BIP62 and BIP146 require.
+    pub fn is_low_s(&self) -> bool {
+        self.is_in_range() && BigUint::from_bytes_be(&self.s) <= curve_order() >> 1
+    }
+
+    /// Replaces a high `s` by `n - s`, an equally valid signature that is standard to relay.
+    /// Out of range signatures are left as they are.
+    pub fn normalize_s(&mut self) {
+        if self.is_in_range() && !self.is_low_s() {
+            let s = curve_order() - BigUint::from_bytes_be(&self.s);
+            let bytes = s.to_bytes_be();
+
+            self.s = [vec![0; 32 - bytes.len()], bytes].concat();
+        }
+    }
+
+    /// Returns every public key for which the signature is valid, with its recovery id.
+    ///
+    /// There are up to four candidates, usually two: ids 2 and 3 need `r + n` to be a valid x
+    /// coordinate, which has a probability of about 2^-128.
+    ///
+    /// # Arguments
+    ///
+    /// * `digest` - The signed 32-byte hash.
+    pub fn recover_public_keys(&self, digest: &[u8]) -> Result<Vec<(u8, PublicKey)>, SignatureError> {
+        if digest.len() != 32 {
+            return Err(SignatureError::InvalidDigestLength(digest.len()));
+        }
+
+        let secp = Secp256k1::verification_only();
+        let message = Message::from_slice(digest)?;
+        let compact = self.to_compact();
+
+        let candidates = (0..4)
+            .filter_map(|id| {
+                let signature = RecoverableSignature::from_compact(&compact, RecoveryId::from_i32(id).ok()?).ok()?;
+                let pubkey = secp.recover_ecdsa(&message, &signature).ok()?;
+
+                Some((id as u8, PublicKey::from_secp256k1(&pubkey, Default::default())))
+            })
+            .collect();
+
+        Ok(candidates)
+    }
+
+    /// Returns the `secp256k1` signature, failing if `r` or `s` are not below the curve order.
+    pub fn to_secp256k1(&self) -> Result<secp256k1::ecdsa::Signature, SignatureError> {
+        Ok(secp256k1::ecdsa::Signature::from_compact(&self.to_compact())?)
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_der()))
+    }
+}
+
+#[cfg(test)]
+mod ecdsa_tests {
+    use super::*;
+
+    const DER: &str = "3045022100934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d802202442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5";
+
+    fn high_s() -> Signature {
+        let mut signature = Signature::from_der(&hex::decode(DER).unwrap()).unwrap();
+        // n - s
+        signature.s = hex::decode("dbbd3162d46e9f9bef7feb87c16dc13b4f6568a87f4e83f728e2443ba586675c").unwrap();
+        signature
+    }
+
+    #[test]
+    fn should_round_trip_der() {
+        let signature = Signature::from_der(&hex::decode(DER).unwrap()).unwrap();
+
+        assert_eq!(hex::encode(&signature.r), "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8");
+        assert_eq!(hex::encode(signature.to_der()), DER);
+        assert_eq!(Signature::from_compact(&signature.to_compact()).unwrap(), signature);
+    }
+
+    #[test]
+    fn should_encode_short_integers() {
+        let signature = Signature {
+            r: [vec![0; 31], vec![0x01]].concat(),
+            s: [vec![0; 31], vec![0x80]].concat(),
+        };
+
+        assert_eq!(hex::encode(signature.to_der()), "300702010102020080");
+        assert_eq!(Signature::from_der(&signature.to_der()).unwrap(), signature);
+    }
+
+    #[test]
+    fn should_throw_error_if_not_strict_der() {
+        let der = hex::decode(DER).unwrap();
+
+        let mut padded = der.clone();
+        padded[1] += 1;
+        padded[3] += 1;
+        padded.insert(4, 0x00);
+        assert_eq!(Signature::from_der(&padded), Err(SignatureError::InvalidDer("R has excessive padding")));
+        assert!(Signature::from_der_lax(&padded).is_ok());
+
+        let mut negative = der.clone();
+        negative[39] |= 0x80;
+        assert_eq!(Signature::from_der(&negative), Err(SignatureError::InvalidDer("S is negative")));
+
+        assert_eq!(Signature::from_der(&der[..70]), Err(SignatureError::InvalidDer("length does not cover the signature")));
+        assert_eq!(Signature::from_compact(&der), Err(SignatureError::InvalidLength(71)));
+    }
+
+    #[test]
+    fn should_recover_signer_among_candidates() {
+        let private_key = crate::key::PrivateKey::from_str("1").unwrap();
+        let digest = vec![0x01; 32];
+        let signature = private_key.sign_ecdsa(&digest).unwrap();
+        let candidates = signature.recover_public_keys(&digest).unwrap();
+
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates
+            .iter()
+            .any(|(_, pubkey)| *pubkey == PublicKey::from_private_key(private_key.clone())));
+        assert!(candidates.iter().all(|(_, pubkey)| pubkey.verify_ecdsa(&digest, &signature).unwrap()));
+    }
+
+    #[test]
+    fn should_normalize_high_s() {
+        let mut signature = high_s();
+        assert!(!signature.is_low_s());
+
+        signature.normalize_s();
+        assert!(signature.is_low_s());
+        assert_eq!(hex::encode(signature.to_der()), DER);
+    }
+
+    #[test]
+    fn should_not_report_out_of_range_s_as_low() {
+        let der = hex::decode(format!("3026020101022100{}", crate::key::N)).unwrap();
+        let mut signature = Signature::from_der(&der).unwrap();
+
+        assert!(!signature.is_in_range());
+        assert!(!signature.is_low_s());
+
+        signature.normalize_s();
+        assert_eq!(hex::encode(&signature.s), crate::key::N.to_lowercase());
+        assert!(!Signature::from_compact(&[0; 64]).unwrap().is_in_range());
+    }
+}
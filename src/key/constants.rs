@@ -1,5 +1,12 @@
+use num::BigUint;
+
 pub const N: &str = "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141";
 
+/// Returns the order n of the secp256k1 group.
+pub fn curve_order() -> BigUint {
+    BigUint::parse_bytes(N.as_bytes(), 16).expect("valid curve order")
+}
+
 pub const PRIVATE_KEY: &str = "1e99423a4ed27608a15a2616a2b0e9e52ced330ac530edcc32c8ffc6a526aedd";
 pub const INVALID_PRIVATE_KEY: &str = "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
 pub const COMPRESSED_PRIVATE_KEY: &str = "1e99423a4ed27608a15a2616a2b0e9e52ced330ac530edcc32c8ffc6a526aedd01";
@@ -11,3 +18,6 @@ pub const UNCOMPRESSED_PUBLIC_KEY: &str = "04f028892bad7ed57d2fb57bf33081d5cfcf6
 
 pub const ADDRESS_FROM_COMPRESSED: &str = "1J7mdg5rbQyUHENYdx39WVWK7fsLpEoXZy";
 pub const ADDRESS_FROM_UNCOMPRESSED: &str = "1424C2F4bC9JidNjjTUZCbUxv6Sa1Mt62x";
+pub const P2SH_P2WPKH_ADDRESS: &str = "3FyC6EYuxW22uj4CaEGjNCjxeg7gHyFeVv";
+pub const P2WPKH_ADDRESS: &str = "bc1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmmfxuck";
+pub const P2TR_ADDRESS: &str = "bc1psce0qeg5n7fy7lmchmsg9nqelq09yflh2fsj02v2jldeyns6zqkqgsjqpt";
@@ -8,6 +8,8 @@ pub trait Key {
     fn as_hex_string(&mut self) -> String;
     fn append_checksum(&mut self) -> ();
     fn hash160(self) -> Vec<u8>;
+    fn sha256(self) -> Vec<u8>;
+    fn tagged_hash(self, tag: &str) -> Vec<u8>;
     fn as_decimal(self) -> String;
 }
 
@@ -48,6 +50,27 @@ impl Key for Vec<u8> {
         buff[0..20].to_vec()
     }
 
+    fn sha256(self) -> Vec<u8> {
+        let mut buff = [0x00; 32];
+
+        let mut hasher = Sha256::new();
+        hasher.input(&self);
+        hasher.result(&mut buff);
+
+        buff.to_vec()
+    }
+
+    /// BIP340 tagged hash: SHA256(SHA256(tag) || SHA256(tag) || self)
+    fn tagged_hash(self, tag: &str) -> Vec<u8> {
+        let mut tag_hash = tag.as_bytes().to_vec().sha256();
+        let mut preimage = tag_hash.clone();
+
+        preimage.append(&mut tag_hash);
+        preimage.extend(self);
+
+        preimage.sha256()
+    }
+
     fn as_decimal(self) -> String {
         format!("{}", BigUint::from_bytes_be(&self))
     }
@@ -131,6 +154,22 @@ mod keys_tests {
         )
     }
 
+    #[test]
+    fn sha256_of_empty_input() {
+        assert_eq!(
+            Vec::new().sha256(),
+            hex::decode("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855").unwrap(),
+        )
+    }
+
+    #[test]
+    fn tagged_hash_of_empty_input() {
+        assert_eq!(
+            Vec::new().tagged_hash("TapTweak"),
+            hex::decode("8aa4229474ab0100b2d6f0687f031d1fc9d8eef92a042ad97d279bff456b15e4").unwrap(),
+        )
+    }
+
     #[test]
     fn as_decimal() {
         let actual = Vec::from_str("ff").unwrap().as_decimal();
@@ -7,6 +7,7 @@ pub use private_key::PrivateKeyError;
 
 mod public_key;
 pub use public_key::PublicKey;
+pub use public_key::PublicKeyError;
 
 mod constants;
 pub use constants::*;
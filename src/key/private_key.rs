@@ -1,12 +1,23 @@
+use secp256k1::{Message, Secp256k1, SecretKey};
+
+use crate::base58decoder::{base58check_decode, Base58Error};
+use crate::ecdsa::{Signature, SignatureError};
+use crate::schnorr::{self, SchnorrError};
 use crate::key::constants::N;
 use crate::key::Key;
+use crate::network::Network;
 use crate::utils::ToByteArray;
 
 #[derive(Debug, PartialEq)]
 pub enum PrivateKeyError {
     GreaterThanCurveOrder,
+    /// Zero is not a valid secret, it has no public key.
+    Zero,
     InvalidSize,
     InvalidHex(hex::FromHexError),
+    InvalidBase58(Base58Error),
+    InvalidVersion(u8),
+    InvalidCompressionFlag(u8),
 }
 
 impl From<hex::FromHexError> for PrivateKeyError {
@@ -15,15 +26,26 @@ impl From<hex::FromHexError> for PrivateKeyError {
     }
 }
 
+impl From<Base58Error> for PrivateKeyError {
+    fn from(err: Base58Error) -> Self {
+        PrivateKeyError::InvalidBase58(err)
+    }
+}
+
 /// A struct representing Secp256k1 private key
 ///
 /// "The private key can be any number between 0 and n - 1, inclusive, where n is a constant
 /// defined as the order of the Secp256k1 elliptic curve."
 ///
 /// n = FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE BAAEDCE6 AF48A03B BFD25E8C D0364141
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PrivateKey {
     pub key: Vec<u8>,
+    /// Whether the key should be paired with a compressed public key, as signaled by the
+    /// 0x01 suffix of a WIF-compressed string, `None` for keys given as hex, which carry no flag.
+    pub compressed: Option<bool>,
+    /// Network the key belongs to, which selects the WIF version byte.
+    pub network: Network,
 }
 
 impl PrivateKey {
@@ -45,16 +67,78 @@ impl PrivateKey {
 
         let key = Vec::from_str(&privkey_as_str)?;
 
+        if key.iter().all(|&byte| byte == 0) {
+            return Err(PrivateKeyError::Zero);
+        }
+
         let less_than_curve_order = key < N.to_string().to_byte_array().unwrap();
 
         match less_than_curve_order {
-            true => Ok(PrivateKey { key }),
+            true => Ok(PrivateKey {
+                key,
+                compressed: None,
+                network: Network::Mainnet,
+            }),
             false => Err(PrivateKeyError::GreaterThanCurveOrder),
         }
     }
 
+    /// Returns a private key struct given a string in the "Wallet Import Format".
+    ///
+    /// The version byte must be 0x80 (mainnet) or 0xef (test networks) and the checksum must
+    /// match the double SHA256 of the version and payload. A trailing 0x01 marks the key as
+    /// compressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `wif` - Private key as a base58check encoded string slice.
+    pub fn from_wif(wif: &str) -> Result<Self, PrivateKeyError> {
+        let data = base58check_decode(wif)?;
+
+        if data.len() != 33 && data.len() != 34 {
+            return Err(PrivateKeyError::InvalidSize);
+        }
+
+        let network = match Network::from_wif_prefix(data[0]) {
+            Some(network) => network,
+            None => return Err(PrivateKeyError::InvalidVersion(data[0])),
+        };
+
+        let compressed = match data.len() {
+            34 if data[33] == 0x01 => true,
+            34 => return Err(PrivateKeyError::InvalidCompressionFlag(data[33])),
+            _ => false,
+        };
+
+        let mut privkey = PrivateKey::from_str(&hex::encode(&data[1..33]))?;
+        privkey.compressed = Some(compressed);
+        privkey.network = network;
+
+        Ok(privkey)
+    }
+
+    /// Returns a private key struct given either its hexadecimal digits or a WIF string.
+    ///
+    /// Inputs made only of hexadecimal digits are treated as hex, anything else as WIF.
+    ///
+    /// # Arguments
+    ///
+    /// * `privkey` - Private key as a hex or WIF string slice.
+    pub fn from_hex_or_wif(privkey: &str) -> Result<Self, PrivateKeyError> {
+        match privkey.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => PrivateKey::from_str(privkey),
+            false => PrivateKey::from_wif(privkey),
+        }
+    }
+
+    /// Returns the same private key bound to the given network.
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
     /// Returns a hexadecimal string representing the private key
-    fn as_hex_string(&self) -> String {
+    pub fn as_hex_string(&self) -> String {
         let mut key = self.key.clone();
 
         key.as_hex_string()
@@ -72,7 +156,7 @@ impl PrivateKey {
     pub fn as_wif(&self) -> String {
         let mut key = self.key.clone();
 
-        key.insert(0, 0x80);
+        key.insert(0, self.network.wif_prefix());
         key.append_checksum();
 
         bs58::encode(key).into_string()
@@ -82,7 +166,7 @@ impl PrivateKey {
     pub fn as_wif_compressed(&self) -> String {
         let mut key = self.key.clone();
 
-        key.insert(0, 0x80);
+        key.insert(0, self.network.wif_prefix());
         key.push(0x01);
         key.append_checksum();
 
@@ -93,12 +177,37 @@ impl PrivateKey {
     pub fn as_decimal(self) -> String {
         self.key.as_decimal()
     }
+
+    /// Signs a digest with ECDSA, deriving the nonce deterministically (RFC6979). The
+    /// signature is normalized to low S.
+    ///
+    /// # Arguments
+    ///
+    /// * `digest` - The 32-byte hash to sign.
+    pub fn sign_ecdsa(&self, digest: &[u8]) -> Result<Signature, SignatureError> {
+        if digest.len() != 32 {
+            return Err(SignatureError::InvalidDigestLength(digest.len()));
+        }
+
+        let secp = Secp256k1::signing_only();
+        let signature = secp.sign_ecdsa(&Message::from_slice(digest)?, &SecretKey::from_slice(&self.key)?);
+
+        Signature::from_compact(&signature.serialize_compact())
+    }
+
+    /// Signs a message with BIP340 Schnorr, see `schnorr::sign`.
+    pub fn sign_schnorr(&self, message: &[u8], aux_rand: Option<&[u8]>) -> Result<Vec<u8>, SchnorrError> {
+        schnorr::sign(self, message, aux_rand)
+    }
 }
 
 #[cfg(test)]
 mod private_key_tests {
     use super::{PrivateKey, PrivateKeyError};
+    use crate::base58decoder::Base58Error;
     use crate::key::constants::{COMPRESSED_PRIVATE_KEY, COMPRESSED_WIF, N, PRIVATE_KEY, WIF};
+    use crate::key::Key;
+    use crate::network::Network;
 
     #[test]
     fn constructor_should_return_private_key() {
@@ -193,4 +302,146 @@ mod private_key_tests {
             expected,
         )
     }
+
+    #[test]
+    fn should_decode_wif() {
+        let pk = PrivateKey::from_wif(WIF).unwrap();
+
+        assert_eq!(pk.as_hex_string(), PRIVATE_KEY);
+        assert_eq!(pk.compressed, Some(false));
+    }
+
+    #[test]
+    fn should_decode_wif_compressed() {
+        let pk = PrivateKey::from_wif(COMPRESSED_WIF).unwrap();
+
+        assert_eq!(pk.as_hex_string(), PRIVATE_KEY);
+        assert_eq!(pk.compressed, Some(true));
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_checksum_is_invalid() {
+        assert_eq!(
+            PrivateKey::from_wif("5J3mBbAH58CpQ3Y5RNJpUKPE62SQ5tfcvU2JpbnkeyhfsYB1Jco"),
+            Err(PrivateKeyError::InvalidBase58(Base58Error::InvalidChecksum)),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_version_is_invalid() {
+        let mut key = hex::decode(PRIVATE_KEY).unwrap();
+        key.insert(0, 0x81);
+        key.append_checksum();
+
+        assert_eq!(
+            PrivateKey::from_wif(&bs58::encode(key).into_string()),
+            Err(PrivateKeyError::InvalidVersion(0x81)),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_compression_flag_is_invalid() {
+        let mut key = hex::decode(PRIVATE_KEY).unwrap();
+        key.insert(0, 0x80);
+        key.push(0x02);
+        key.append_checksum();
+
+        assert_eq!(
+            PrivateKey::from_wif(&bs58::encode(key).into_string()),
+            Err(PrivateKeyError::InvalidCompressionFlag(0x02)),
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_wif_has_invalid_size() {
+        let mut key = hex::decode(PRIVATE_KEY).unwrap();
+        key.insert(0, 0x80);
+        key.truncate(21);
+        key.append_checksum();
+
+        assert_eq!(
+            PrivateKey::from_wif(&bs58::encode(key).into_string()),
+            Err(PrivateKeyError::InvalidSize),
+        );
+        assert_eq!(
+            PrivateKey::from_wif("1111"),
+            Err(PrivateKeyError::InvalidBase58(Base58Error::TooShort(4))),
+        )
+    }
+
+    #[test]
+    fn should_accept_either_hex_or_wif() {
+        let hex = PrivateKey::from_hex_or_wif(PRIVATE_KEY).unwrap();
+        let wif = PrivateKey::from_hex_or_wif(COMPRESSED_WIF).unwrap();
+
+        assert_eq!(hex.key, wif.key);
+        assert_eq!(hex.compressed, None);
+        assert_eq!(wif.compressed, Some(true));
+    }
+
+    #[test]
+    fn should_return_expected_testnet_wif_formats() {
+        let pk = PrivateKey::from_str(PRIVATE_KEY)
+            .unwrap()
+            .with_network(Network::Regtest);
+
+        assert_eq!(
+            pk.as_wif(),
+            "91pPmKypfMGxN73N3iCjLuwBjgo7F4CpGQtFuE9FziSieVTY4jn"
+        );
+        assert_eq!(
+            pk.as_wif_compressed(),
+            "cNcBUemoNGVRN9fRtxrmtteAPQeWZ399d2REmX1TBjvWpRfNMy91"
+        );
+    }
+
+    #[test]
+    fn should_decode_testnet_wif() {
+        let pk = PrivateKey::from_wif("cNcBUemoNGVRN9fRtxrmtteAPQeWZ399d2REmX1TBjvWpRfNMy91").unwrap();
+
+        assert_eq!(pk.as_hex_string(), PRIVATE_KEY);
+        assert_eq!(pk.network, Network::Testnet);
+        assert_eq!(pk.compressed, Some(true));
+    }
+
+    #[test]
+    fn should_throw_error_if_key_is_zero() {
+        let mut key = vec![0x80];
+        key.extend([0; 32]);
+        key.push(0x01);
+        key.append_checksum();
+
+        assert_eq!(PrivateKey::from_hex_or_wif("0"), Err(PrivateKeyError::Zero));
+        assert_eq!(
+            PrivateKey::from_wif(&bs58::encode(key).into_string()),
+            Err(PrivateKeyError::Zero),
+        )
+    }
+
+    #[test]
+    fn should_return_rfc6979_signature() {
+        let digest = "Satoshi Nakamoto".as_bytes().to_vec().sha256();
+        let signature = PrivateKey::from_str("1").unwrap().sign_ecdsa(&digest).unwrap();
+
+        assert_eq!(
+            hex::encode(signature.to_der()),
+            "3045022100934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d802202442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+
+        let n_minus_one = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140";
+        let signature = PrivateKey::from_str(n_minus_one).unwrap().sign_ecdsa(&digest).unwrap();
+
+        assert_eq!(
+            hex::encode(signature.to_der()),
+            "3045022100fd567d121db66e382991534ada77a6bd3106f0a1098c231e47993447cd6af2d002206b39cd0eb1bc8603e159ef5c20a5c8ad685a45b06ce9bebed3f153d10d93bed5"
+        );
+    }
+
+    #[test]
+    fn should_throw_error_if_digest_is_not_32_bytes() {
+        assert_eq!(
+            PrivateKey::from_str(PRIVATE_KEY).unwrap().sign_ecdsa(&[0; 31]),
+            Err(crate::ecdsa::SignatureError::InvalidDigestLength(31))
+        );
+    }
 }
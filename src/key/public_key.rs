@@ -1,12 +1,43 @@
+use crate::address::AddressType;
+use crate::bech32;
+use crate::ecdsa::{Signature, SignatureError};
+use crate::schnorr::{self, SchnorrError};
 use crate::key::{Key, PrivateKey, PrivateKeyError};
-use secp256k1::{rand, Secp256k1, SecretKey};
+use crate::network::Network;
+use crate::vanity::{self, Pattern, VanityError, VanityResult};
+use secp256k1::{rand, Message, Secp256k1, SecretKey, XOnlyPublicKey};
 
 type Coordinates = (String, String);
 
 #[derive(Debug, PartialEq)]
+pub enum PublicKeyError {
+    InvalidHex(hex::FromHexError),
+    InvalidLength(usize),
+    /// The bytes don't encode a point of the curve.
+    InvalidPoint(secp256k1::Error),
+    /// The taproot tweak gives no valid output key.
+    InvalidTweak(secp256k1::Error),
+    /// Only P2PKH, P2SH-P2WPKH, P2WPKH and P2TR addresses pay to a single key.
+    UnsupportedAddressType(AddressType),
+}
+
+impl From<hex::FromHexError> for PublicKeyError {
+    fn from(err: hex::FromHexError) -> Self {
+        PublicKeyError::InvalidHex(err)
+    }
+}
+
+impl From<secp256k1::Error> for PublicKeyError {
+    fn from(err: secp256k1::Error) -> Self {
+        PublicKeyError::InvalidPoint(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct PublicKey {
     pub compressed: Vec<u8>,
     pub uncompressed: Vec<u8>,
+    pub network: Network,
 }
 
 impl PublicKey {
@@ -20,18 +51,97 @@ impl PublicKey {
         PublicKey {
             compressed: pubkey.serialize().to_vec(),
             uncompressed: pubkey.serialize_uncompressed().to_vec(),
+            network: pk.network,
+        }
+    }
+
+    /// Returns the public key wrapping a `secp256k1` public key, bound to `network`.
+    pub fn from_secp256k1(pubkey: &secp256k1::PublicKey, network: Network) -> Self {
+        PublicKey {
+            compressed: pubkey.serialize().to_vec(),
+            uncompressed: pubkey.serialize_uncompressed().to_vec(),
+            network,
+        }
+    }
+
+    /// Returns a public key given its 33-byte compressed or 65-byte uncompressed serialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized point, which must lie on the curve.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, PublicKeyError> {
+        if bytes.len() != 33 && bytes.len() != 65 {
+            return Err(PublicKeyError::InvalidLength(bytes.len()));
         }
+
+        let pubkey = secp256k1::PublicKey::from_slice(bytes)?;
+
+        Ok(PublicKey::from_secp256k1(&pubkey, Network::Mainnet))
+    }
+
+    /// Returns a public key given its compressed or uncompressed serialization as hex.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkey` - 66 or 130 hexadecimal digits.
+    pub fn from_hex(pubkey: &str) -> Result<Self, PublicKeyError> {
+        PublicKey::from_slice(&hex::decode(pubkey)?)
+    }
+
+    /// Returns a public key given its affine coordinates, the inverse of `get_coordinates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate as up to 64 hexadecimal digits.
+    /// * `y` - The y coordinate as up to 64 hexadecimal digits.
+    pub fn from_coordinates(x: &str, y: &str) -> Result<Self, PublicKeyError> {
+        let mut uncompressed = vec![0x04];
+
+        for coordinate in [x, y] {
+            let bytes = hex::decode(format!("{:0>64}", coordinate))?;
+
+            if bytes.len() != 32 {
+                return Err(PublicKeyError::InvalidLength(bytes.len()));
+            }
+
+            uncompressed.extend(bytes);
+        }
+
+        PublicKey::from_slice(&uncompressed)
+    }
+
+    /// Returns a public key given its BIP340 x-only serialization and the parity of y.
+    ///
+    /// # Arguments
+    ///
+    /// * `x_only` - The 32-byte x coordinate.
+    /// * `odd_y` - Whether y is odd. x-only keys in BIP340 and taproot imply an even y.
+    pub fn from_x_only(x_only: &[u8], odd_y: bool) -> Result<Self, PublicKeyError> {
+        if x_only.len() != 32 {
+            return Err(PublicKeyError::InvalidLength(x_only.len()));
+        }
+
+        let mut compressed = vec![if odd_y { 0x03 } else { 0x02 }];
+        compressed.extend_from_slice(x_only);
+
+        PublicKey::from_slice(&compressed)
+    }
+
+    /// Returns the same public key bound to the given network.
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
     }
 
     pub fn from_private_key_string(pk: &str) -> Result<Self, PrivateKeyError> {
-        let pk = PrivateKey::from_str(pk)?;
+        let pk = PrivateKey::from_hex_or_wif(pk)?;
 
         Ok(PublicKey::from_private_key(pk))
     }
 
     pub fn get_address_from_compressed(self) -> String {
         let mut pkh = self.compressed.hash160();
-        pkh.insert(0, 0x00);
+        pkh.insert(0, self.network.p2pkh_prefix());
         pkh.append_checksum();
 
         bs58::encode(&pkh).into_string()
@@ -39,46 +149,149 @@ impl PublicKey {
 
     pub fn get_address_from_uncompressed(self) -> String {
         let mut pkh = self.uncompressed.hash160();
-        pkh.insert(0, 0x00);
+        pkh.insert(0, self.network.p2pkh_prefix());
         pkh.append_checksum();
 
         bs58::encode(&pkh).into_string()
     }
 
-    pub fn get_coordinates(self) -> Coordinates {
-        (
-            hex::encode(&self.uncompressed[1..33]),
-            hex::encode(&self.uncompressed[33..]),
-        )
+    /// Returns the native segwit (P2WPKH) address of the compressed public key, bech32 encoded.
+    pub fn get_p2wpkh_address(self) -> String {
+        let pkh = self.compressed.hash160();
+
+        bech32::encode_segwit_address(self.network.bech32_hrp(), 0, &pkh)
     }
 
-    pub fn vanity_address(vanity: &str) -> String {
-        loop {
-            let secp = Secp256k1::new();
+    /// Returns the P2WPKH witness program used as redeem script by nested segwit addresses:
+    /// `OP_0 <20 bytes> <hash160 of the compressed public key>`.
+    pub fn p2wpkh_redeem_script(&self) -> Vec<u8> {
+        let mut script = vec![0x00, 0x14];
+        script.append(&mut self.compressed.clone().hash160());
 
-            let secret_key = SecretKey::new(&mut rand::thread_rng());
+        script
+    }
 
-            let pubkey = secp256k1::PublicKey::from_secret_key(
-                &secp,
-                &secret_key,
-            );
+    /// Returns the nested segwit (P2SH-P2WPKH) address, base58check encoding the hash160 of the
+    /// P2WPKH redeem script.
+    pub fn get_p2sh_p2wpkh_address(self) -> String {
+        let mut sh = self.p2wpkh_redeem_script().hash160();
+        sh.insert(0, self.network.p2sh_prefix());
+        sh.append_checksum();
 
-            let pubkey = PublicKey {
-                compressed: pubkey.serialize().to_vec(),
-                uncompressed: pubkey.serialize().to_vec(),
-            };
+        bs58::encode(&sh).into_string()
+    }
 
-            let compressed_address = &pubkey.get_address_from_compressed();
-            let prefix = &compressed_address.as_str()[1 .. vanity.len() + 1];
+    /// Returns the BIP340 x-only public key, i.e. the compressed key without its parity byte.
+    pub fn x_only(&self) -> Vec<u8> {
+        self.compressed[1..33].to_vec()
+    }
 
-            if prefix == vanity {
-                return compressed_address.to_string()
+    /// Returns the BIP341 taproot output key, tweaking the x-only internal key with
+    /// `tagged_hash("TapTweak", x_only || merkle_root)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `merkle_root` - The 32-byte root of the script tree, if any.
+    pub fn taproot_output_key(&self, merkle_root: Option<&[u8]>) -> Result<Vec<u8>, secp256k1::Error> {
+        let mut tweak = self.x_only();
+
+        if let Some(root) = merkle_root {
+            if root.len() != 32 {
+                return Err(secp256k1::Error::InvalidTweak);
             }
+
+            tweak.extend_from_slice(root);
         }
+
+        let secp = Secp256k1::verification_only();
+        let mut output_key = XOnlyPublicKey::from_slice(&self.x_only())?;
+        output_key.tweak_add_assign(&secp, &tweak.tagged_hash("TapTweak"))?;
+
+        Ok(output_key.serialize().to_vec())
+    }
+
+    /// Returns the taproot (P2TR) address of the tweaked output key, bech32m encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `merkle_root` - The 32-byte root of the script tree, `None` for key path only outputs.
+    pub fn get_p2tr_address(self, merkle_root: Option<&[u8]>) -> Result<String, secp256k1::Error> {
+        let output_key = self.taproot_output_key(merkle_root)?;
+
+        Ok(bech32::encode_segwit_address(self.network.bech32_hrp(), 1, &output_key))
+    }
+
+    /// Returns the address of the given type paying to the compressed key, P2SH addresses
+    /// nesting a P2WPKH script and P2TR ones committing to no script.
+    ///
+    /// # Arguments
+    ///
+    /// * `address_type` - P2PKH, P2SH, P2WPKH or P2TR.
+    pub fn get_address(self, address_type: AddressType) -> Result<String, PublicKeyError> {
+        match address_type {
+            AddressType::P2pkh => Ok(self.get_address_from_compressed()),
+            AddressType::P2sh => Ok(self.get_p2sh_p2wpkh_address()),
+            AddressType::P2wpkh => Ok(self.get_p2wpkh_address()),
+            AddressType::P2tr => self.get_p2tr_address(None).map_err(PublicKeyError::InvalidTweak),
+            _ => Err(PublicKeyError::UnsupportedAddressType(address_type)),
+        }
+    }
+
+    /// Returns true if the ECDSA signature is valid for the digest and this key.
+    ///
+    /// High S signatures are normalized before verifying, since consensus accepts them; use
+    /// `Signature::is_low_s` to enforce the relay policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `digest` - The signed 32-byte hash.
+    /// * `signature` - The signature to verify.
+    pub fn verify_ecdsa(&self, digest: &[u8], signature: &Signature) -> Result<bool, SignatureError> {
+        if digest.len() != 32 {
+            return Err(SignatureError::InvalidDigestLength(digest.len()));
+        }
+
+        let mut signature = signature.to_secp256k1()?;
+        signature.normalize_s();
+
+        let secp = Secp256k1::verification_only();
+        let pubkey = secp256k1::PublicKey::from_slice(&self.compressed)?;
+
+        Ok(secp.verify_ecdsa(&Message::from_slice(digest)?, &signature, &pubkey).is_ok())
+    }
+
+    /// Returns true if the BIP340 signature is valid for the x-only form of this key.
+    pub fn verify_schnorr(&self, message: &[u8], signature: &[u8]) -> Result<bool, SchnorrError> {
+        schnorr::verify(&self.x_only(), message, signature)
+    }
+
+    pub fn get_coordinates(self) -> Coordinates {
+        (
+            hex::encode(&self.uncompressed[1..33]),
+            hex::encode(&self.uncompressed[33..]),
+        )
+    }
+
+    /// Returns a mainnet P2PKH address whose characters after the leading `1` start with
+    /// `vanity`, with its keys, searching on every core.
+    pub fn vanity_address(vanity: &str) -> Result<VanityResult, VanityError> {
+        let pattern = Pattern::prefix(vanity)?;
+
+        vanity::search(&[pattern], Network::Mainnet, vanity::default_threads())
     }
 
     /// Returns a new address from an compressed public key, derived from a random secret key.
     pub fn get_new_address() -> String {
+        PublicKey::get_new_address_for_network(Network::Mainnet)
+    }
+
+    /// Returns a new address for the given network, derived from a random secret key.
+    pub fn get_new_address_for_network(network: Network) -> String {
+        PublicKey::new_random(network).get_address_from_compressed()
+    }
+
+    /// Returns a public key derived from a random secret key.
+    pub fn new_random(network: Network) -> Self {
         let secp = Secp256k1::new();
         let secret_key = SecretKey::new(&mut rand::thread_rng());
 
@@ -87,12 +300,11 @@ impl PublicKey {
             &secret_key,
         );
 
-        let pubkey = PublicKey {
+        PublicKey {
             compressed: pubkey.serialize().to_vec(),
-            uncompressed: pubkey.serialize().to_vec(),
-        };
-
-        pubkey.get_address_from_compressed()
+            uncompressed: pubkey.serialize_uncompressed().to_vec(),
+            network,
+        }
     }
 }
 
@@ -164,6 +376,120 @@ mod public_key_tests {
 
     }
 
+    #[test]
+    fn should_return_expected_p2wpkh_address() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2wpkh_address(),
+            constants::P2WPKH_ADDRESS,
+        )
+    }
+
+    #[test]
+    fn should_return_expected_regtest_p2wpkh_address() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY)
+            .unwrap()
+            .with_network(Network::Regtest);
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2wpkh_address(),
+            "bcrt1qh0q7g23e6pdye3sh2ttfvwmld8gfhvnmnxyz5v",
+        )
+    }
+
+    #[test]
+    fn should_return_expected_redeem_script() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.p2wpkh_redeem_script(),
+            hex::decode("0014bbc1e42a39d05a4cc61752d6963b7f69d09bb27b").unwrap(),
+        )
+    }
+
+    #[test]
+    fn should_return_expected_p2sh_p2wpkh_address() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2sh_p2wpkh_address(),
+            constants::P2SH_P2WPKH_ADDRESS,
+        )
+    }
+
+    #[test]
+    fn should_return_expected_testnet_p2sh_p2wpkh_address() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY)
+            .unwrap()
+            .with_network(Network::Testnet);
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2sh_p2wpkh_address(),
+            "2N7XQ9yUwZxXP7WgkFMtbz9jDs2Kr2njYRy",
+        )
+    }
+
+    #[test]
+    fn should_return_expected_x_only_key() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.x_only(),
+            hex::decode(&constants::COMPRESSED_PUBLIC_KEY[2..]).unwrap(),
+        )
+    }
+
+    #[test]
+    fn should_return_expected_p2tr_address() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2tr_address(None).unwrap(),
+            constants::P2TR_ADDRESS,
+        )
+    }
+
+    #[test]
+    fn should_return_bip86_p2tr_address() {
+        let pk = PrivateKey::from_wif("KyRv5iFPHG7iB5E4CqvMzH3WFJVhbfYK4VY7XAedd9Ys69mEsPLQ").unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2tr_address(None).unwrap(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        )
+    }
+
+    #[test]
+    fn should_tweak_with_merkle_root() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2tr_address(Some(&[0x00; 32])).unwrap(),
+            "bc1puwq6xnkrm9gvms88lnxkdmvl7rrn0t0pnplcsmqk80faypza3wvsep6tnp",
+        )
+    }
+
+    #[test]
+    fn should_throw_error_if_merkle_root_is_not_32_bytes() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_p2tr_address(Some(&[0x00; 31])),
+            Err(secp256k1::Error::InvalidTweak),
+        )
+    }
+
     #[test]
     fn should_return_expected_coordinates_from_public_key() {
         let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
@@ -181,9 +507,10 @@ mod public_key_tests {
     #[test]
     fn should_return_a_vanity_address() {
         let prefix = "Lo";
-        let vanity_address = PublicKey::vanity_address(prefix);
+        let result = PublicKey::vanity_address(prefix).unwrap();
 
-        assert_eq!(&vanity_address[1..3], "Lo");
+        assert_eq!(&result.address[1..3], "Lo");
+        assert_eq!(PublicKey::from_private_key(result.private_key).get_address_from_compressed(), result.address);
     }
 
     #[test]
@@ -194,4 +521,64 @@ mod public_key_tests {
         assert!(address.len() >= 26);
         assert!(address.len() <= 36);
     }
+
+    #[test]
+    fn should_return_expected_testnet_address() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY)
+            .unwrap()
+            .with_network(Network::Signet);
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert_eq!(
+            public_key.get_address_from_compressed(),
+            "mxdivjAqQSQj4LrAMX1XLQidyfU3pCWeS7",
+        )
+    }
+
+    #[test]
+    fn should_return_a_testnet_address() {
+        let address = PublicKey::get_new_address_for_network(Network::Regtest);
+
+        assert!(address.starts_with('m') || address.starts_with('n'));
+    }
+
+    #[test]
+    fn should_verify_ecdsa_signature() {
+        let pk = PrivateKey::from_str(constants::PRIVATE_KEY).unwrap();
+        let digest = vec![0x01; 32];
+        let mut signature = pk.sign_ecdsa(&digest).unwrap();
+        let public_key = PublicKey::from_private_key(pk);
+
+        assert!(public_key.verify_ecdsa(&digest, &signature).unwrap());
+        assert!(!public_key.verify_ecdsa(&[0x02; 32], &signature).unwrap());
+
+        signature.r[31] ^= 0x01;
+        assert!(!public_key.verify_ecdsa(&digest, &signature).unwrap());
+    }
+
+    #[test]
+    fn should_return_public_key_given_its_serialization() {
+        let expected = PublicKey::from_private_key(PrivateKey::from_str(constants::PRIVATE_KEY).unwrap());
+
+        assert_eq!(PublicKey::from_hex(constants::COMPRESSED_PUBLIC_KEY).unwrap(), expected);
+        assert_eq!(PublicKey::from_hex(constants::UNCOMPRESSED_PUBLIC_KEY).unwrap(), expected);
+
+        let (x, y) = expected.clone().get_coordinates();
+        assert_eq!(PublicKey::from_coordinates(&x, &y).unwrap(), expected);
+
+        let x_only = hex::decode(&constants::COMPRESSED_PUBLIC_KEY[2..]).unwrap();
+        assert_eq!(PublicKey::from_x_only(&x_only, true).unwrap(), expected);
+        assert_ne!(PublicKey::from_x_only(&x_only, false).unwrap(), expected);
+    }
+
+    #[test]
+    fn should_throw_error_if_not_a_curve_point() {
+        assert!(matches!(PublicKey::from_x_only(&[0; 32], false), Err(PublicKeyError::InvalidPoint(_))));
+        assert!(matches!(
+            PublicKey::from_coordinates(&constants::COMPRESSED_PUBLIC_KEY[2..], "01"),
+            Err(PublicKeyError::InvalidPoint(_))
+        ));
+        assert_eq!(PublicKey::from_hex("02f0"), Err(PublicKeyError::InvalidLength(2)));
+        assert!(matches!(PublicKey::from_hex("zz"), Err(PublicKeyError::InvalidHex(_))));
+    }
 }
@@ -1,3 +1,15 @@
 pub mod utils;
 pub mod key;
 pub mod base58decoder;
+pub mod network;
+pub mod bech32;
+pub mod address;
+pub mod base58encoder;
+pub mod bip32;
+pub mod bip39;
+pub mod descriptor;
+pub mod bip137;
+pub mod bip322;
+pub mod ecdsa;
+pub mod schnorr;
+pub mod vanity;
@@ -0,0 +1,146 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// The Bitcoin network a key or an address belongs to.
+///
+/// Testnet, signet and regtest share the same base58 version bytes, only their bech32 human
+/// readable parts differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    /// Returns the version byte prepended to a private key in the "Wallet Import Format".
+    pub fn wif_prefix(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x80,
+            _ => 0xef,
+        }
+    }
+
+    /// Returns the version byte of a pay-to-public-key-hash address.
+    pub fn p2pkh_prefix(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x00,
+            _ => 0x6f,
+        }
+    }
+
+    /// Returns the version byte of a pay-to-script-hash address.
+    pub fn p2sh_prefix(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x05,
+            _ => 0xc4,
+        }
+    }
+
+    /// Returns the human readable part of a bech32 encoded segwit address.
+    pub fn bech32_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet | Network::Signet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+
+    /// Returns the version bytes of a serialized BIP32 extended private key (xprv or tprv).
+    pub fn xprv_version(&self) -> [u8; 4] {
+        match self {
+            Network::Mainnet => [0x04, 0x88, 0xad, 0xe4],
+            _ => [0x04, 0x35, 0x83, 0x94],
+        }
+    }
+
+    /// Returns the version bytes of a serialized BIP32 extended public key (xpub or tpub).
+    pub fn xpub_version(&self) -> [u8; 4] {
+        match self {
+            Network::Mainnet => [0x04, 0x88, 0xb2, 0x1e],
+            _ => [0x04, 0x35, 0x87, 0xcf],
+        }
+    }
+
+    /// Returns the network matching a WIF version byte.
+    ///
+    /// Since test networks share the same prefix, 0xef always maps to `Network::Testnet`.
+    pub fn from_wif_prefix(prefix: u8) -> Option<Self> {
+        match prefix {
+            0x80 => Some(Network::Mainnet),
+            0xef => Some(Network::Testnet),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" | "main" | "bitcoin" => Ok(Network::Mainnet),
+            "testnet" | "test" => Ok(Network::Testnet),
+            "signet" => Ok(Network::Signet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(format!("unknown network: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Signet => "signet",
+            Network::Regtest => "regtest",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod network_tests {
+    use super::Network;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_return_expected_prefixes() {
+        assert_eq!(Network::Mainnet.wif_prefix(), 0x80);
+        assert_eq!(Network::Mainnet.p2pkh_prefix(), 0x00);
+        assert_eq!(Network::Mainnet.p2sh_prefix(), 0x05);
+
+        for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+            assert_eq!(network.wif_prefix(), 0xef);
+            assert_eq!(network.p2pkh_prefix(), 0x6f);
+            assert_eq!(network.p2sh_prefix(), 0xc4);
+        }
+    }
+
+    #[test]
+    fn should_return_expected_extended_key_versions() {
+        assert_eq!(Network::Mainnet.xprv_version(), [0x04, 0x88, 0xad, 0xe4]);
+        assert_eq!(Network::Mainnet.xpub_version(), [0x04, 0x88, 0xb2, 0x1e]);
+        assert_eq!(Network::Regtest.xprv_version(), [0x04, 0x35, 0x83, 0x94]);
+        assert_eq!(Network::Regtest.xpub_version(), [0x04, 0x35, 0x87, 0xcf]);
+    }
+
+    #[test]
+    fn should_return_expected_hrp() {
+        assert_eq!(Network::Mainnet.bech32_hrp(), "bc");
+        assert_eq!(Network::Testnet.bech32_hrp(), "tb");
+        assert_eq!(Network::Signet.bech32_hrp(), "tb");
+        assert_eq!(Network::Regtest.bech32_hrp(), "bcrt");
+    }
+
+    #[test]
+    fn should_parse_network_names() {
+        assert_eq!(Network::from_str("regtest"), Ok(Network::Regtest));
+        assert_eq!(Network::from_str("Signet"), Ok(Network::Signet));
+        assert!(Network::from_str("litecoin").is_err());
+    }
+}
@@ -0,0 +1,370 @@
+use num::{BigUint, Zero};
+use secp256k1::{rand, schnorr, KeyPair, Message, Secp256k1, SecretKey, XOnlyPublicKey};
+
+use crate::key::{curve_order, Key, PrivateKey};
+
+#[derive(Debug, PartialEq)]
+pub enum SchnorrError {
+    InvalidSignatureLength(usize),
+    InvalidPublicKeyLength(usize),
+    InvalidAuxLength(usize),
+    InvalidKey(secp256k1::Error),
+}
+
+impl From<secp256k1::Error> for SchnorrError {
+    fn from(err: secp256k1::Error) -> Self {
+        SchnorrError::InvalidKey(err)
+    }
+}
+
+fn check_lengths(public_key: &[u8], signature: &[u8]) -> Result<(), SchnorrError> {
+    if public_key.len() != 32 {
+        return Err(SchnorrError::InvalidPublicKeyLength(public_key.len()));
+    }
+    if signature.len() != 64 {
+        return Err(SchnorrError::InvalidSignatureLength(signature.len()));
+    }
+
+    Ok(())
+}
+
+/// Signs a message with BIP340, returning the 64-byte signature.
+///
+/// Messages of 32 bytes, the usual hashes, are signed by libsecp256k1 and other lengths follow
+/// the BIP340 algorithm step by step.
+///
+/// # Arguments
+///
+/// * `private_key` - The signing key, negated by the signer if its public key has an odd y.
+/// * `message` - The message, of any length.
+/// * `aux_rand` - 32 bytes of auxiliary randomness, fresh random bytes if `None`.
+pub fn sign(private_key: &PrivateKey, message: &[u8], aux_rand: Option<&[u8]>) -> Result<Vec<u8>, SchnorrError> {
+    let aux_rand: [u8; 32] = match aux_rand {
+        Some(aux) => aux.try_into().map_err(|_| SchnorrError::InvalidAuxLength(aux.len()))?,
+        None => rand::random(),
+    };
+
+    if message.len() != 32 {
+        return sign_any_length(private_key, message, &aux_rand);
+    }
+
+    let secp = Secp256k1::signing_only();
+    let keypair = KeyPair::from_secret_key(&secp, SecretKey::from_slice(&private_key.key)?);
+    let signature = secp.sign_schnorr_with_aux_rand(&Message::from_slice(message)?, &keypair, &aux_rand);
+
+    Ok(signature.as_ref().to_vec())
+}
+
+/// Returns true if the BIP340 signature is valid. Public keys or signatures that are not
+/// valid points and scalars fail verification rather than returning an error.
+///
+/// Like `sign`, messages that are not 32 bytes follow the BIP340 algorithm step by step.
+///
+/// # Arguments
+///
+/// * `public_key` - The 32-byte x-only public key.
+/// * `message` - The message, of any length.
+/// * `signature` - The 64-byte signature.
+pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool, SchnorrError> {
+    check_lengths(public_key, signature)?;
+
+    if message.len() != 32 {
+        return Ok(verify_any_length(public_key, message, signature));
+    }
+
+    let secp = Secp256k1::verification_only();
+
+    let verified = match (XOnlyPublicKey::from_slice(public_key), schnorr::Signature::from_slice(signature)) {
+        (Ok(public_key), Ok(signature)) => secp
+            .verify_schnorr(&signature, &Message::from_slice(message)?, &public_key)
+            .is_ok(),
+        _ => false,
+    };
+
+    Ok(verified)
+}
+
+/// Signs as BIP340 describes, for messages libsecp256k1 can't sign.
+fn sign_any_length(private_key: &PrivateKey, message: &[u8], aux_rand: &[u8]) -> Result<Vec<u8>, SchnorrError> {
+    let secp = Secp256k1::signing_only();
+    let n = curve_order();
+
+    let point = secp256k1::PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&private_key.key)?);
+    let (public_key, odd) = x_only(&point);
+
+    let d = BigUint::from_bytes_be(&private_key.key);
+    let d = if odd { &n - d } else { d };
+
+    let mask = aux_rand.to_vec().tagged_hash("BIP0340/aux");
+    let t: Vec<u8> = scalar_bytes(&d).iter().zip(mask).map(|(d, mask)| d ^ mask).collect();
+
+    let nonce = [&t[..], &public_key, message].concat().tagged_hash("BIP0340/nonce");
+    let k = BigUint::from_bytes_be(&nonce) % &n;
+    let nonce_point = secp256k1::PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&scalar_bytes(&k))?);
+    let (r, odd) = x_only(&nonce_point);
+    let k = if odd { &n - k } else { k };
+
+    let e = challenge(&r, &public_key, message, &n);
+    let s = (k + e * d) % &n;
+
+    Ok([&r[..], &scalar_bytes(&s)].concat())
+}
+
+/// Verifies as BIP340 describes, checking that `sG - eP` has an even y and `r` as x.
+fn verify_any_length(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let secp = Secp256k1::new();
+    let n = curve_order();
+
+    let (r, s) = signature.split_at(32);
+    let s = BigUint::from_bytes_be(s);
+
+    let point = match lift_x(public_key) {
+        Some(point) if s < n => point,
+        _ => return false,
+    };
+
+    let e = challenge(r, public_key, message, &n);
+
+    // sG and -eP, either being the point at infinity when its scalar is zero
+    let s_point = SecretKey::from_slice(&scalar_bytes(&s)).ok().map(|s| secp256k1::PublicKey::from_secret_key(&secp, &s));
+    let e_point = match e.is_zero() {
+        true => None,
+        false => {
+            let mut e_point = point;
+            e_point.negate_assign(&secp);
+            e_point.mul_assign(&secp, &scalar_bytes(&e)).ok().map(|_| e_point)
+        }
+    };
+
+    let nonce = match (s_point, e_point) {
+        (Some(s_point), Some(e_point)) => s_point.combine(&e_point).ok(),
+        (Some(point), None) | (None, Some(point)) => Some(point),
+        (None, None) => None,
+    };
+
+    match nonce.map(|nonce| x_only(&nonce)) {
+        Some((x, false)) => x == r,
+        _ => false,
+    }
+}
+
+/// Returns the x coordinate of a point and whether its y is odd.
+fn x_only(point: &secp256k1::PublicKey) -> (Vec<u8>, bool) {
+    let serialized = point.serialize();
+
+    (serialized[1..].to_vec(), serialized[0] == 0x03)
+}
+
+/// Returns the BIP340 challenge `e = H(r || P || m) mod n`.
+fn challenge(r: &[u8], public_key: &[u8], message: &[u8], n: &BigUint) -> BigUint {
+    let hash = [r, public_key, message].concat().tagged_hash("BIP0340/challenge");
+
+    BigUint::from_bytes_be(&hash) % n
+}
+
+/// Returns the point with x coordinate `x` and an even y, if any.
+fn lift_x(x: &[u8]) -> Option<secp256k1::PublicKey> {
+    secp256k1::PublicKey::from_slice(&[&[0x02], x].concat()).ok()
+}
+
+fn scalar_bytes(scalar: &BigUint) -> Vec<u8> {
+    let bytes = scalar.to_bytes_be();
+
+    [vec![0; 32 - bytes.len()], bytes].concat()
+}
+
+/// Returns true if all BIP340 signatures are valid, checking them at once with a random
+/// linear combination: `(Σ aᵢsᵢ)G = Σ aᵢRᵢ + Σ aᵢeᵢPᵢ`, with `a₁ = 1`.
+///
+/// Degenerate sums, which random coefficients make practically impossible, fall back to
+/// verifying each signature on its own.
+///
+/// # Arguments
+///
+/// * `items` - The `(x-only public key, message, signature)` triples, with 32-byte keys and
+///   64-byte signatures.
+pub fn verify_batch(items: &[(&[u8], &[u8], &[u8])]) -> Result<bool, SchnorrError> {
+    for (public_key, _, signature) in items {
+        check_lengths(public_key, signature)?;
+    }
+
+    let secp = Secp256k1::new();
+    let n = curve_order();
+
+    let mut s_sum = BigUint::zero();
+    let mut points = Vec::new();
+
+    for (i, (public_key, message, signature)) in items.iter().enumerate() {
+        let (r, s) = signature.split_at(32);
+        let s = BigUint::from_bytes_be(s);
+
+        let (point, nonce) = match (lift_x(public_key), lift_x(r)) {
+            (Some(point), Some(nonce)) if s < n => (point, nonce),
+            _ => return Ok(false),
+        };
+
+        let e = challenge(r, public_key, message, &n);
+        let a = match i {
+            0 => BigUint::from(1u8),
+            _ => BigUint::from_bytes_be(&rand::random::<[u8; 32]>()) % &n,
+        };
+
+        let mut a_nonce = nonce;
+        let mut ae_point = point;
+        let scaled = a_nonce
+            .mul_assign(&secp, &scalar_bytes(&a))
+            .and(ae_point.mul_assign(&secp, &scalar_bytes(&(&a * e % &n))));
+
+        if scaled.is_err() {
+            return verify_each(items);
+        }
+
+        s_sum = (s_sum + a * s) % &n;
+        points.push(a_nonce);
+        points.push(ae_point);
+    }
+
+    if items.is_empty() {
+        return Ok(true);
+    }
+
+    let refs: Vec<&secp256k1::PublicKey> = points.iter().collect();
+    let rhs = secp256k1::PublicKey::combine_keys(&refs);
+    let lhs = SecretKey::from_slice(&scalar_bytes(&s_sum)).map(|s| secp256k1::PublicKey::from_secret_key(&secp, &s));
+
+    match (lhs, rhs) {
+        (Ok(lhs), Ok(rhs)) => Ok(lhs == rhs),
+        _ => verify_each(items),
+    }
+}
+
+fn verify_each(items: &[(&[u8], &[u8], &[u8])]) -> Result<bool, SchnorrError> {
+    for (public_key, message, signature) in items {
+        if !verify(public_key, message, signature)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod schnorr_tests {
+    use super::*;
+    use crate::key::PublicKey;
+
+    /// The BIP340 test vectors: index, secret key, public key, aux_rand, message, signature,
+    /// verification result and comment.
+    const TEST_VECTORS: &str = include_str!("test_vectors.csv");
+
+    struct Vector {
+        secret_key: Option<Vec<u8>>,
+        public_key: Vec<u8>,
+        aux_rand: Vec<u8>,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+        result: bool,
+    }
+
+    fn vectors() -> Vec<Vector> {
+        TEST_VECTORS
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+
+                Vector {
+                    secret_key: Some(fields[1]).filter(|key| !key.is_empty()).map(|key| hex::decode(key).unwrap()),
+                    public_key: hex::decode(fields[2]).unwrap(),
+                    aux_rand: hex::decode(fields[3]).unwrap(),
+                    message: hex::decode(fields[4]).unwrap(),
+                    signature: hex::decode(fields[5]).unwrap(),
+                    result: fields[6] == "TRUE",
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_pass_bip340_test_vectors() {
+        for vector in vectors() {
+            if let Some(secret_key) = &vector.secret_key {
+                let private_key = PrivateKey::from_str(&hex::encode(secret_key)).unwrap();
+
+                assert_eq!(
+                    private_key.sign_schnorr(&vector.message, Some(&vector.aux_rand)).unwrap(),
+                    vector.signature
+                );
+                assert_eq!(PublicKey::from_private_key(private_key).x_only(), vector.public_key);
+            }
+
+            assert_eq!(
+                verify(&vector.public_key, &vector.message, &vector.signature).unwrap(),
+                vector.result
+            );
+        }
+    }
+
+    #[test]
+    fn should_pass_bip340_test_vectors_of_any_length() {
+        for vector in vectors() {
+            if let Some(secret_key) = &vector.secret_key {
+                let private_key = PrivateKey::from_str(&hex::encode(secret_key)).unwrap();
+
+                assert_eq!(
+                    sign_any_length(&private_key, &vector.message, &vector.aux_rand).unwrap(),
+                    vector.signature
+                );
+            }
+
+            assert_eq!(
+                verify_any_length(&vector.public_key, &vector.message, &vector.signature),
+                vector.result
+            );
+        }
+    }
+
+    #[test]
+    fn should_batch_verify_test_vectors() {
+        fn items<'a>(vectors: &[&'a Vector]) -> Vec<(&'a [u8], &'a [u8], &'a [u8])> {
+            vectors
+                .iter()
+                .map(|v| (&v.public_key[..], &v.message[..], &v.signature[..]))
+                .collect()
+        }
+
+        let vectors = vectors();
+
+        let valid: Vec<&Vector> = vectors.iter().filter(|v| v.result).collect();
+        assert!(verify_batch(&items(&valid)).unwrap());
+        assert!(verify_batch(&[]).unwrap());
+
+        for invalid in vectors.iter().filter(|v| !v.result) {
+            let mut batch = valid.clone();
+            batch.insert(1, invalid);
+
+            assert!(!verify_batch(&items(&batch)).unwrap());
+        }
+    }
+
+    #[test]
+    fn should_sign_with_random_aux() {
+        let private_key = PrivateKey::from_str(crate::key::PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_private_key(private_key.clone());
+
+        for message in [&[0x42; 32][..], &[0x42; 31], &[]] {
+            let signature = private_key.sign_schnorr(message, None).unwrap();
+
+            assert!(public_key.verify_schnorr(message, &signature).unwrap());
+        }
+    }
+
+    #[test]
+    fn should_throw_error_if_invalid_lengths() {
+        let private_key = PrivateKey::from_str(crate::key::PRIVATE_KEY).unwrap();
+
+        assert_eq!(private_key.sign_schnorr(&[0; 32], Some(&[0; 16])), Err(SchnorrError::InvalidAuxLength(16)));
+        assert_eq!(verify(&[0; 33], &[0; 32], &[0; 64]), Err(SchnorrError::InvalidPublicKeyLength(33)));
+        assert_eq!(verify(&[0; 32], &[0; 32], &[0; 65]), Err(SchnorrError::InvalidSignatureLength(65)));
+    }
+}
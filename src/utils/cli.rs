@@ -1,33 +1,155 @@
-use crate::key::{PublicKey, PrivateKey};
-use crate::base58decoder::base58decode;
+use crate::address::{self, Address};
+use crate::bip137::{self, SignatureType};
+use crate::bip322::{self, SignatureFormat};
+use crate::bip39::{Language, Mnemonic};
+use crate::descriptor::{Descriptor, DescriptorError, KeySource};
+use crate::ecdsa::{Signature, SignatureError};
+use crate::bip32::slip132::{self, KeyVersion};
+use crate::bip32::{Bip32Error, DerivationPath, ExtendedPrivateKey, ExtendedPublicKey, Purpose, HARDENED};
+use crate::key::{PublicKey, PublicKeyError, PrivateKey, PrivateKeyError};
+use crate::base58decoder::{base58check_decode, base58decode, guess_payload_type};
+use crate::base58encoder::base58check_encode;
+use crate::network::Network;
+use crate::schnorr;
+use crate::utils::ToByteArray;
+use crate::vanity::{self, Difficulty, Pattern, PatternKind, VanityError, VanityStats};
 
-use clap::{Args, Parser, Subcommand};
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
     #[clap(subcommand)]
     commands: Commands,
+
+    /// Network used for keys and addresses: mainnet, testnet, signet or regtest.
+    /// Defaults to the network of the input key, or mainnet.
+    #[clap(long, global = true, value_parser)]
+    network: Option<Network>,
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
-    /// Logs the address derived from a compressed public key, given private key.
-    GetCompressedAddressFrom(PrivKeyArg),
+    /// Logs the address derived from a compressed public key, given the private or public key.
+    GetCompressedAddressFrom(KeyArg),
+
+    /// Logs the address derived from a uncompressed public key, given the private or public key.
+    GetUncompressedAddressFrom(KeyArg),
+
+    /// Logs the native segwit (P2WPKH) address derived from a compressed public key, given the private or public key.
+    GetSegwitAddressFrom(KeyArg),
 
-    /// Logs the address derived from a uncompressed public key, given the private key.
-    GetUncompressedAddressFrom(PrivKeyArg),
+    /// Logs the nested segwit (P2SH-P2WPKH) address and its redeem script, given the private or public key.
+    GetNestedSegwitAddressFrom(KeyArg),
+
+    /// Logs the taproot (P2TR) address derived from the tweaked x-only public key, given the private or public key.
+    GetTaprootAddressFrom(TaprootArg),
 
     /// Logs the public key coordinates, given the private key.
-    GetCoordinatesFrom(PrivKeyArg),
+    GetCoordinatesFrom(KeyArg),
 
     /// Generates and logs an address from a random private key.
-    GetAddress,
+    GetAddress {
+        /// Type of the generated address
+        #[clap(long = "type", value_enum, default_value = "legacy")]
+        address_type: AddressType,
+    },
 
-    /// Computes a vanity address given the desired prefix.
-    GetVanity {
+    /// Generates, validates and converts BIP39 mnemonic phrases.
+    #[clap(subcommand)]
+    Mnemonic(MnemonicCommands),
+
+    /// Derives and logs the BIP32 key at a path, given the wallet seed or an xprv.
+    DeriveKey(HdPathArg),
+
+    /// Derives and logs the address of the BIP32 key at a path, given the wallet seed or an xprv.
+    DeriveAddress {
+        #[clap(flatten)]
+        key: HdPathArg,
+
+        /// Type of the derived address
+        #[clap(long = "type", value_enum, default_value = "legacy")]
+        address_type: AddressType,
+    },
+
+    /// Logs a table of receive and change addresses of a BIP44, 49, 84 or 86 account.
+    DeriveAddresses {
+        /// Mnemonic phrase, wallet seed as hexadecimal digits, or an extended private key
+        #[clap(value_parser)]
+        root: String,
+
+        /// Derivation purpose: 44 (legacy), 49 (nested segwit), 84 (native segwit) or 86 (taproot)
+        #[clap(long, value_parser, default_value = "84")]
+        purpose: Purpose,
+
+        /// Account index
+        #[clap(long, value_parser, default_value_t = 0)]
+        account: u32,
+
+        /// Index of the first address
+        #[clap(long, value_parser, default_value_t = 0)]
+        start: u32,
+
+        /// Number of receive and of change addresses
+        #[clap(long, value_parser, default_value_t = 10)]
+        count: u32,
+
+        /// Passphrase of the mnemonic
+        #[clap(long, value_parser, default_value = "")]
+        passphrase: String,
+
+        #[clap(flatten)]
+        language: LanguageArg,
+    },
+
+    /// Logs the fields of a serialized extended key (xprv, xpub, tprv or tpub).
+    InspectXkey {
+        #[clap(value_parser)]
+        xkey: String,
+    },
+
+    /// Converts an extended key between SLIP-132 versions, e.g. zpub to xpub.
+    ConvertXkey {
+        #[clap(value_parser)]
+        xkey: String,
+
+        /// Target prefix, e.g. xpub, ypub, zpub, Zprv or vpub. Defaults to the plain BIP32
+        /// version (xpub, xprv, tpub or tprv) of the same network.
+        #[clap(long, value_parser)]
+        to: Option<String>,
+    },
+
+    /// Validates an output descriptor, adds or verifies its checksum and logs its addresses.
+    Descriptor {
         #[clap(value_parser)]
-        prefix: String,
+        descriptor: String,
+
+        /// First child index of ranged descriptors
+        #[clap(long, value_parser, default_value_t = 0)]
+        start: u32,
+
+        /// Number of addresses to log for ranged descriptors
+        #[clap(long, value_parser, default_value_t = 5)]
+        count: u32,
+    },
+
+    /// Computes a vanity address matching the desired prefix or any of the other patterns.
+    GetVanity {
+        #[clap(flatten)]
+        patterns: VanityArg,
+
+        /// Number of worker threads, defaults to one per core
+        #[clap(long, value_parser = clap::value_parser!(u16).range(1..))]
+        threads: Option<u16>,
+
+        /// Writes the private key to this new file, readable only by its owner, instead of
+        /// logging it
+        #[clap(long, value_parser)]
+        secret_file: Option<std::path::PathBuf>,
     },
 
     /// Logs the compressed private key as a hex string
@@ -39,57 +161,915 @@ enum Commands {
     /// Logs the private key using the "Compressed Wallet Import Format"
     GetWifCompressed(PrivKeyArg),
 
+    /// Decodes a "Wallet Import Format" string and logs the private key as hex
+    DecodeWif {
+        #[clap(value_parser)]
+        wif: String,
+    },
+
     /// Decodes and logs the provided input
     Base58Decode {
         #[clap(value_parser)]
         encoded: String,
+    },
+
+    /// Encodes the provided version and payload as a base58check string
+    Base58Encode {
+        /// Version bytes as hex, e.g. 80 for WIF or 0488b21e for xpub
+        #[clap(value_parser)]
+        version: String,
+
+        /// Payload bytes as hex
+        #[clap(value_parser)]
+        payload: String,
+    },
+
+    /// Validates an address and logs its network, type and scriptPubKey
+    ValidateAddress {
+        #[clap(value_parser)]
+        address: String,
+    },
+
+    /// Signs a message (BIP137), logging the base64 signature and the signing address.
+    SignMessage {
+        #[clap(flatten)]
+        key: PrivKeyArg,
+
+        #[clap(value_parser)]
+        message: String,
+
+        /// Type of the signing address, recorded in the signature header
+        #[clap(long = "type", value_enum, default_value = "legacy")]
+        address_type: AddressType,
+
+        /// Sign for the legacy address of the uncompressed public key, the default for an
+        /// uncompressed WIF
+        #[clap(long, value_parser)]
+        uncompressed: bool,
+    },
+
+    /// Verifies a BIP137 message signature against a P2PKH, P2SH-P2WPKH or P2WPKH address.
+    VerifyMessage {
+        #[clap(value_parser)]
+        address: String,
+
+        #[clap(value_parser)]
+        message: String,
+
+        /// Base64 encoded signature
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Signs a message for a segwit or taproot address (BIP322), logging the base64 signature.
+    SignBip322 {
+        #[clap(flatten)]
+        key: PrivKeyArg,
+
+        #[clap(value_parser)]
+        message: String,
+
+        /// Type of the signing address: segwit or taproot
+        #[clap(long = "type", value_enum, default_value = "segwit")]
+        address_type: AddressType,
+
+        /// Return the whole to_sign transaction instead of its witness only
+        #[clap(long, value_parser)]
+        full: bool,
+    },
+
+    /// Verifies a simple or full BIP322 signature against a P2WPKH or P2TR address.
+    VerifyBip322 {
+        #[clap(value_parser)]
+        address: String,
+
+        #[clap(value_parser)]
+        message: String,
+
+        /// Base64 encoded signature
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Signs a 32-byte hash with ECDSA (RFC6979 nonce, low S), logging DER and compact encodings.
+    SignHash {
+        #[clap(flatten)]
+        key: PrivKeyArg,
+
+        /// The 32-byte hash as hexadecimal digits
+        #[clap(value_parser)]
+        hash: String,
+    },
+
+    /// Verifies an ECDSA signature of a 32-byte hash against a public key.
+    VerifySig {
+        /// Public key as 33 or 65 bytes of hex
+        #[clap(value_parser)]
+        public_key: String,
+
+        /// The 32-byte hash as hexadecimal digits
+        #[clap(value_parser)]
+        hash: String,
+
+        /// DER (optionally followed by a sighash byte) or compact signature, as hex
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Logs r, s and the BIP66 strict DER and low S compliance of an ECDSA signature.
+    InspectSig {
+        /// DER (optionally followed by a sighash byte) or compact signature, as hex
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Recovers the candidate public keys and addresses of the signer of a 32-byte hash.
+    RecoverPubkey {
+        /// The signed 32-byte hash as hexadecimal digits
+        #[clap(value_parser)]
+        hash: String,
+
+        /// Compact signature as hex (64 bytes, or 65 with a BIP137 header byte) or base64
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Signs a message with BIP340 Schnorr, logging the x-only public key and signature.
+    SchnorrSign {
+        #[clap(flatten)]
+        key: PrivKeyArg,
+
+        /// The message as hexadecimal digits, usually a 32-byte hash
+        #[clap(value_parser)]
+        message: String,
+
+        /// 32 bytes of auxiliary randomness as hex, random if omitted
+        #[clap(long, value_parser)]
+        aux: Option<String>,
+    },
+
+    /// Verifies a BIP340 Schnorr signature against an x-only public key.
+    SchnorrVerify {
+        /// The 32-byte x-only public key as hex
+        #[clap(value_parser)]
+        public_key: String,
+
+        /// The message as hexadecimal digits, usually a 32-byte hash
+        #[clap(value_parser)]
+        message: String,
+
+        /// The 64-byte signature as hex
+        #[clap(value_parser)]
+        signature: String,
+    },
+
+    /// Batch verifies BIP340 signatures read from a file, one "public_key message signature"
+    /// hex triple per line.
+    SchnorrVerifyBatch {
+        #[clap(value_parser)]
+        file: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum MnemonicCommands {
+    /// Generates and logs a mnemonic from secure random entropy.
+    New {
+        /// Number of words: 12, 15, 18, 21 or 24
+        #[clap(long, value_parser, default_value_t = 12)]
+        words: usize,
+
+        #[clap(flatten)]
+        language: LanguageArg,
+    },
+
+    /// Validates the words and checksum of a mnemonic.
+    Check {
+        #[clap(value_parser)]
+        phrase: String,
+
+        #[clap(flatten)]
+        language: LanguageArg,
+    },
+
+    /// Logs the seed derived from a mnemonic and an optional passphrase.
+    Seed {
+        #[clap(value_parser)]
+        phrase: String,
+
+        /// Optional passphrase, the "25th word"
+        #[clap(long, value_parser, default_value = "")]
+        passphrase: String,
+
+        #[clap(flatten)]
+        language: LanguageArg,
+    },
+}
+
+#[derive(Debug, Args)]
+struct LanguageArg {
+    /// Language of the wordlist, e.g. english, japanese or spanish
+    #[clap(long, value_parser, default_value = "english")]
+    language: Language,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AddressType {
+    /// Base58 pay-to-public-key-hash address (1...)
+    Legacy,
+    /// Base58 nested segwit P2SH-P2WPKH address (3...)
+    Nested,
+    /// Bech32 native segwit P2WPKH address (bc1q...)
+    Segwit,
+    /// Bech32m taproot P2TR address (bc1p...)
+    Taproot,
+}
+
+impl From<AddressType> for address::AddressType {
+    fn from(address_type: AddressType) -> Self {
+        match address_type {
+            AddressType::Legacy => address::AddressType::P2pkh,
+            AddressType::Nested => address::AddressType::P2sh,
+            AddressType::Segwit => address::AddressType::P2wpkh,
+            AddressType::Taproot => address::AddressType::P2tr,
+        }
     }
 }
 
 #[derive(Debug, Args)]
 struct PrivKeyArg {
+    /// Private key as hexadecimal digits or in the "Wallet Import Format"
     #[clap(value_parser)]
     private_key: String,
 }
 
+#[derive(Debug, Args)]
+#[clap(group(ArgGroup::new("key").required(true).args(&["private-key", "pubkey"])))]
+struct KeyArg {
+    /// Private key as hexadecimal digits or in the "Wallet Import Format"
+    #[clap(value_parser)]
+    private_key: Option<String>,
+
+    /// Public key as 33 or 65 bytes of hex instead of a private key. Taproot addresses also
+    /// take a 32-byte x-only key
+    #[clap(long, value_parser)]
+    pubkey: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[clap(group(
+    ArgGroup::new("pattern")
+        .required(true)
+        .multiple(true)
+        .args(&["prefix", "suffix", "contains", "regex", "patterns-file"])
+))]
+struct VanityArg {
+    /// Characters wanted right after the leading character of the address, or after bc1q and
+    /// bc1p for segwit and taproot
+    #[clap(value_parser)]
+    prefix: Option<String>,
+
+    /// Characters wanted at the end of the address, can be repeated
+    #[clap(long, value_parser)]
+    suffix: Vec<String>,
+
+    /// Characters wanted anywhere after the prefix position, can be repeated
+    #[clap(long, value_parser)]
+    contains: Vec<String>,
+
+    /// Regular expression matched against the whole address, can be repeated
+    #[clap(long, value_parser)]
+    regex: Vec<String>,
+
+    /// File with one alternative pattern per line: `prefix:`, `suffix:`, `contains:` or
+    /// `regex:` followed by the pattern, or a bare prefix
+    #[clap(long, value_parser)]
+    patterns_file: Option<std::path::PathBuf>,
+
+    /// Matches upper and lower case letters alike
+    #[clap(short, long, action)]
+    ignore_case: bool,
+
+    /// Type of the searched address, segwit and taproot patterns use the bech32 characters
+    #[clap(long = "type", value_enum, default_value = "legacy")]
+    address_type: AddressType,
+}
+
+#[derive(Debug, Args)]
+struct HdPathArg {
+    /// Wallet seed as hexadecimal digits, or an extended private key
+    #[clap(value_parser)]
+    root: String,
+
+    /// Derivation path, e.g. m/84'/0'/0'/0/5
+    #[clap(value_parser)]
+    path: String,
+}
+
+#[derive(Debug, Args)]
+struct TaprootArg {
+    #[clap(flatten)]
+    key: KeyArg,
+
+    /// Merkle root of the script tree, as 32 bytes of hex
+    #[clap(long, value_parser)]
+    merkle_root: Option<String>,
+}
+
 pub fn run() {
     let cli = Cli::parse();
+    let network = cli.network;
 
     match cli.commands {
-        Commands::GetCompressedAddressFrom(arg) => log_compressed_address(&arg.private_key),
-        Commands::GetUncompressedAddressFrom(arg) => log_uncompressed_address(&arg.private_key),
-        Commands::GetCoordinatesFrom(arg) => log_coordinates(&arg.private_key),
-        Commands::GetAddress => println!("{}", PublicKey::get_new_address()),
-        Commands::GetVanity { prefix } => println!("{}", PublicKey::vanity_address(&prefix)),
+        Commands::GetCompressedAddressFrom(arg) => log_compressed_address(&arg, network),
+        Commands::GetUncompressedAddressFrom(arg) => log_uncompressed_address(&arg, network),
+        Commands::GetSegwitAddressFrom(arg) => log_segwit_address(&arg, network),
+        Commands::GetNestedSegwitAddressFrom(arg) => log_nested_segwit_address(&arg, network),
+        Commands::GetTaprootAddressFrom(arg) => log_taproot_address(&arg.key, arg.merkle_root.as_deref(), network),
+        Commands::GetCoordinatesFrom(arg) => log_coordinates(&arg, network),
+        Commands::GetAddress { address_type } => log_new_address(address_type, network),
+        Commands::Mnemonic(command) => match command {
+            MnemonicCommands::New { words, language } => log_new_mnemonic(words, language.language),
+            MnemonicCommands::Check { phrase, language } => log_checked_mnemonic(&phrase, language.language),
+            MnemonicCommands::Seed { phrase, passphrase, language } => log_mnemonic_seed(&phrase, &passphrase, language.language),
+        },
+        Commands::DeriveKey(arg) => log_derived_key(&arg, network),
+        Commands::DeriveAddress { key, address_type } => log_derived_address(&key, address_type, network),
+        Commands::DeriveAddresses { root, purpose, account, start, count, passphrase, language } => {
+            let root = parse_wallet_root(&root, &passphrase, language.language, network);
+            match unhardened_range(start, count) {
+                Ok(range) => log_derived_addresses(root, purpose, account, range),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+        Commands::InspectXkey { xkey } => log_inspected_xkey(&xkey),
+        Commands::ConvertXkey { xkey, to } => log_converted_xkey(&xkey, to.as_deref()),
+        Commands::Descriptor { descriptor, start, count } => {
+            match unhardened_range(start, count) {
+                Ok(range) => log_descriptor(&descriptor, range, network),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+        Commands::GetVanity { patterns, threads, secret_file } => {
+            log_vanity_address(&patterns, threads, secret_file.as_deref(), network)
+        }
 
         Commands::GetHexCompressed(arg) => log_hex_compressed_private_key(&arg.private_key),
-        Commands::GetWif(arg) => log_wif_format(&arg.private_key),
-        Commands::GetWifCompressed(arg) => log_wif_compressed_format(&arg.private_key),
+        Commands::GetWif(arg) => log_wif_format(&arg.private_key, network),
+        Commands::GetWifCompressed(arg) => log_wif_compressed_format(&arg.private_key, network),
+        Commands::DecodeWif { wif } => log_decoded_wif(&wif),
 
-        Commands::Base58Decode { encoded } => log_base58_decoded(&encoded)
+        Commands::Base58Decode { encoded } => log_base58_decoded(&encoded),
+        Commands::Base58Encode { version, payload } => log_base58_encoded(&version, &payload),
+        Commands::ValidateAddress { address } => log_validated_address(&address),
+        Commands::SignMessage { key, message, address_type, uncompressed } => {
+            log_signed_message(&key.private_key, &message, address_type, uncompressed, network)
+        }
+        Commands::VerifyMessage { address, message, signature } => log_verified_message(&address, &message, &signature),
+        Commands::SignBip322 { key, message, address_type, full } => {
+            log_bip322_signed_message(&key.private_key, &message, address_type, full, network)
+        }
+        Commands::VerifyBip322 { address, message, signature } => log_bip322_verified_message(&address, &message, &signature),
+        Commands::SignHash { key, hash } => log_signed_hash(&key.private_key, &hash, network),
+        Commands::VerifySig { public_key, hash, signature } => log_verified_signature(&public_key, &hash, &signature),
+        Commands::InspectSig { signature } => log_inspected_signature(&signature),
+        Commands::RecoverPubkey { hash, signature } => log_recovered_public_keys(&hash, &signature, network),
+        Commands::SchnorrSign { key, message, aux } => log_schnorr_signature(&key.private_key, &message, aux.as_deref(), network),
+        Commands::SchnorrVerify { public_key, message, signature } => {
+            log_verified_schnorr_signature(&public_key, &message, &signature)
+        }
+        Commands::SchnorrVerifyBatch { file } => log_batch_verified_schnorr_signatures(&file),
     }
 }
 
-fn log_compressed_address(private_key: &str) {
-    let k = PublicKey::from_private_key_string(&private_key);
+/// Parses a hex or WIF private key, switching it to `network` when one is given.
+fn parse_private_key(private_key: &str, network: Option<Network>) -> Result<PrivateKey, PrivateKeyError> {
+    let privkey = PrivateKey::from_hex_or_wif(private_key)?;
+
+    match network {
+        Some(network) => Ok(privkey.with_network(network)),
+        None => Ok(privkey),
+    }
+}
+
+/// Returns the public key given with `--pubkey`, or the one of the given private key.
+///
+/// An x-only key leaves out the parity of y, which changes every address but taproot ones, so
+/// it is only accepted with `allow_x_only`.
+fn parse_public_key(arg: &KeyArg, network: Option<Network>, allow_x_only: bool) -> Result<PublicKey, String> {
+    match (&arg.private_key, &arg.pubkey) {
+        (_, Some(pubkey)) => {
+            let r = match pubkey.len() {
+                64 if !allow_x_only => {
+                    return Err(String::from(
+                        "Error parsing public key: x-only keys only make taproot addresses, give the 33-byte key",
+                    ))
+                }
+                64 => hex::decode(pubkey)
+                    .map_err(PublicKeyError::from)
+                    .and_then(|x_only| PublicKey::from_x_only(&x_only, false)),
+                _ => PublicKey::from_hex(pubkey),
+            };
+
+            r.map(|pubkey| pubkey.with_network(network.unwrap_or_default()))
+                .map_err(|error| format!("Error parsing public key: {:?}", error))
+        }
+        (Some(private_key), None) => parse_private_key(private_key, network)
+            .map(PublicKey::from_private_key)
+            .map_err(|error| format!("Error getting address from private key string: {:?}", error)),
+        (None, None) => Err(String::from("Expected a private key or --pubkey")),
+    }
+}
+
+fn log_compressed_address(arg: &KeyArg, network: Option<Network>) {
+    let k = parse_public_key(arg, network, false);
 
     match k {
         Ok(pubkey) => println!("{}", pubkey.get_address_from_compressed()),
-        Err(error) => eprintln!("Error getting address from private key string: {:?}", error),
+        Err(error) => eprintln!("{}", error),
     }
 }
 
-fn log_uncompressed_address(private_key: &str) {
-    let k = PublicKey::from_private_key_string(&private_key);
+fn log_uncompressed_address(arg: &KeyArg, network: Option<Network>) {
+    let k = parse_public_key(arg, network, false);
 
     match k {
         Ok(pubkey) => println!("{}", pubkey.get_address_from_uncompressed()),
-        Err(error) => eprintln!("Error getting address from private key string: {:?}", error),
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+fn log_segwit_address(arg: &KeyArg, network: Option<Network>) {
+    let k = parse_public_key(arg, network, false);
+
+    match k {
+        Ok(pubkey) => println!("{}", pubkey.get_p2wpkh_address()),
+        Err(error) => eprintln!("{}", error),
     }
 }
 
-fn log_coordinates(private_key: &str) {
-    let k = PublicKey::from_private_key_string(&private_key);
+fn log_nested_segwit_address(arg: &KeyArg, network: Option<Network>) {
+    let k = parse_public_key(arg, network, false);
+
+    match k {
+        Ok(pubkey) => {
+            println!("Redeem script: {}", hex::encode(pubkey.p2wpkh_redeem_script()));
+            println!("Address: {}", pubkey.get_p2sh_p2wpkh_address());
+        }
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+fn log_new_address(address_type: AddressType, network: Option<Network>) {
+    let pubkey = PublicKey::new_random(network.unwrap_or_default());
+
+    match pubkey.get_address(address_type.into()) {
+        Ok(address) => println!("{}", address),
+        Err(error) => eprintln!("Error getting address: {:?}", error),
+    }
+}
+
+fn parse_vanity_patterns(arg: &VanityArg) -> Result<Vec<Pattern>, String> {
+    let address_type = arg.address_type.into();
+
+    let mut patterns = Vec::new();
+    let kinds = [
+        (PatternKind::Prefix, arg.prefix.as_slice()),
+        (PatternKind::Suffix, arg.suffix.as_slice()),
+        (PatternKind::Contains, arg.contains.as_slice()),
+        (PatternKind::Regex, arg.regex.as_slice()),
+    ];
+
+    for (kind, texts) in kinds {
+        for text in texts {
+            let pattern = Pattern::new(kind, text, arg.ignore_case, address_type).map_err(|error| describe_vanity_error(&error))?;
+            patterns.push(pattern);
+        }
+    }
+
+    if let Some(file) = &arg.patterns_file {
+        let contents = std::fs::read_to_string(file).map_err(|error| format!("Error reading {}: {}", file.display(), error))?;
+        let parsed = vanity::parse_patterns(&contents, arg.ignore_case, address_type).map_err(|error| describe_vanity_error(&error))?;
+        patterns.extend(parsed);
+    }
+
+    Ok(patterns)
+}
+
+fn log_vanity_address(arg: &VanityArg, threads: Option<u16>, secret_file: Option<&std::path::Path>, network: Option<Network>) {
+    if let Some(path) = secret_file.filter(|path| path.exists()) {
+        eprintln!("Error: {} already exists", path.display());
+        std::process::exit(1);
+    }
+
+    let network = network.unwrap_or_default();
+    let r = parse_vanity_patterns(arg).and_then(|patterns| {
+        vanity::patterns_difficulty(&patterns, network)
+            .map(|difficulty| (patterns, difficulty))
+            .map_err(|error| describe_vanity_error(&error))
+    });
+
+    let (patterns, difficulty) = match r {
+        Ok(r) => r,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+
+    println!(
+        "Difficulty: 1 in {:.0}, 50% chance after {:.0} keys",
+        difficulty.expected_attempts(),
+        difficulty.attempts_for_probability(0.5)
+    );
+
+    let threads = threads.map_or_else(vanity::default_threads, usize::from);
+    let show_progress = std::io::stderr().is_terminal();
+    let r = vanity::search_with_progress(&patterns, network, threads, Duration::from_millis(500), |stats| {
+        if show_progress {
+            eprint!("\r\x1b[K{}", format_vanity_progress(stats, &difficulty));
+        }
+    });
+
+    if show_progress {
+        eprint!("\r\x1b[K");
+    }
+
+    let result = match r {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("{}", describe_vanity_error(&error));
+            std::process::exit(1);
+        }
+    };
+
+    println!("Address: {}", result.address);
+    println!("Public key: {}", hex::encode(&result.public_key.compressed));
+
+    match secret_file {
+        Some(path) => match result.write_secret(path) {
+            Ok(()) => println!("Private key written to {}", path.display()),
+            Err(error) => {
+                eprintln!("Error writing private key to {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        },
+        None => {
+            println!("Private key: {}", result.private_key.as_hex_string());
+            println!("WIF compressed: {}", result.private_key.as_wif_compressed());
+        }
+    }
+
+    println!(
+        "{} keys in {:.2}s ({:.0} keys/s, {} threads)",
+        result.stats.attempts,
+        result.stats.elapsed.as_secs_f64(),
+        result.stats.keys_per_second(),
+        threads
+    );
+}
+
+fn format_vanity_progress(stats: &VanityStats, difficulty: &Difficulty) -> String {
+    let eta = match difficulty.eta(stats, 0.5) {
+        Some(eta) if eta.is_zero() => "past the median".to_string(),
+        Some(eta) => format!("50% ETA {}", format_duration(eta)),
+        None => "50% ETA unknown".to_string(),
+    };
+
+    format!(
+        "{} keys, {:.0} keys/s, {:.1}% chance so far, {}",
+        stats.attempts,
+        stats.keys_per_second(),
+        difficulty.probability_within(stats.attempts) * 100.0,
+        eta
+    )
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        3600..=86399 => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        86400..=31_557_599 => format!("{}d {}h", secs / 86400, secs % 86400 / 3600),
+        _ => format!("{:.1e} years", secs as f64 / 31_557_600.0),
+    }
+}
+
+fn describe_vanity_error(error: &VanityError) -> String {
+    match error {
+        VanityError::InvalidCharacter(c) => {
+            format!("Invalid pattern: '{}' is not in the base58 alphabet, which leaves out 0, O, I and l", c)
+        }
+        VanityError::TooLong { length, max, address_type } => {
+            let fixed = match address_type {
+                address::AddressType::P2wpkh => "bc1q, tb1q or bcrt1q",
+                address::AddressType::P2tr => "bc1p, tb1p or bcrt1p",
+                _ => "the first character",
+            };
+
+            format!("Invalid pattern: {} characters, {} addresses have at most {} after {}", length, address_type, max, fixed)
+        }
+        VanityError::Unreachable { prefix, first_characters } => format!(
+            "Invalid prefix: no address of this network has '{}' after its first character, which must be followed by one of {}",
+            prefix, first_characters
+        ),
+        VanityError::InvalidRegex(error) => format!("Invalid regex: {}", error),
+        VanityError::UnknownKind(kind) => {
+            format!("Invalid pattern: unknown kind '{}', expected prefix, suffix, contains or regex", kind)
+        }
+        VanityError::InvalidLine { line, error } => format!("Line {}: {}", line, describe_vanity_error(error)),
+        VanityError::InvalidBech32Character(c) => format!(
+            "Invalid pattern: '{}' is not in the bech32 charset, which leaves out 1, b, i, o and upper case letters",
+            c
+        ),
+        VanityError::NoPattern => "No pattern to search for".to_string(),
+        VanityError::MixedAddressTypes => "Patterns must all be for the same address type".to_string(),
+        VanityError::UnsupportedType(address_type) => format!("No vanity search for {} addresses", address_type),
+        VanityError::NoSampledMatch { regex, samples } => format!(
+            "Refusing to search: none of {} random addresses matched the regex '{}', which may never match",
+            samples, regex
+        ),
+    }
+}
+
+fn log_new_mnemonic(words: usize, language: Language) {
+    match Mnemonic::generate(words, language) {
+        Ok(mnemonic) => println!("{}", mnemonic.phrase()),
+        Err(error) => eprintln!("Error generating mnemonic: {:?}", error),
+    }
+}
+
+fn log_checked_mnemonic(phrase: &str, language: Language) {
+    match Mnemonic::from_phrase(phrase, language) {
+        Ok(mnemonic) => {
+            println!("Valid: true");
+            println!("Words: {}", mnemonic.indices.len());
+            println!("Entropy: {}", hex::encode(mnemonic.entropy()));
+        }
+        Err(error) => {
+            eprintln!("Invalid mnemonic: {:?}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn log_mnemonic_seed(phrase: &str, passphrase: &str, language: Language) {
+    match Mnemonic::from_phrase(phrase, language) {
+        Ok(mnemonic) => println!("{}", hex::encode(mnemonic.to_seed(passphrase))),
+        Err(error) => eprintln!("Invalid mnemonic: {:?}", error),
+    }
+}
+
+/// Parses a hex seed or an xprv into the root extended private key.
+///
+/// The root of a seed belongs to `network`, or mainnet; an xprv keeps its own network unless
+/// `network` is given.
+fn parse_root_key(root: &str, network: Option<Network>) -> Result<ExtendedPrivateKey, String> {
+    let key = match root.chars().all(|c| c.is_ascii_hexdigit()) {
+        true => {
+            let seed = hex::decode(root).map_err(|error| format!("Error decoding seed: {:?}", error))?;
+
+            ExtendedPrivateKey::from_seed(&seed, network.unwrap_or_default())
+        }
+        false => ExtendedPrivateKey::from_base58check(root),
+    };
+
+    let mut key = key.map_err(|error| format!("Error parsing root key: {:?}", error))?;
+
+    if let Some(network) = network {
+        key.network = network;
+        key.private_key.network = network;
+    }
+
+    Ok(key)
+}
+
+/// Parses a mnemonic phrase, a hex seed or an xprv into the root extended private key.
+fn parse_wallet_root(root: &str, passphrase: &str, language: Language, network: Option<Network>) -> Result<ExtendedPrivateKey, String> {
+    if !root.trim().contains(char::is_whitespace) {
+        return parse_root_key(root, network);
+    }
+
+    let mnemonic = Mnemonic::from_phrase(root, language).map_err(|error| format!("Invalid mnemonic: {:?}", error))?;
+
+    parse_root_key(&hex::encode(mnemonic.to_seed(passphrase)), network)
+}
+
+/// Derives the extended private key at `arg.path` from the seed or xprv in `arg.root`.
+fn derive_key(arg: &HdPathArg, network: Option<Network>) -> Result<ExtendedPrivateKey, String> {
+    let path = DerivationPath::from_str(&arg.path).map_err(|error| format!("Error parsing path: {:?}", error))?;
+
+    parse_root_key(&arg.root, network)?
+        .derive_path(&path)
+        .map_err(|error| format!("Error deriving key: {:?}", error))
+}
+
+fn log_derived_key(arg: &HdPathArg, network: Option<Network>) {
+    match derive_key(arg, network) {
+        Ok(key) => {
+            println!("Path: {}", arg.path);
+            println!("Depth: {}", key.depth);
+            println!("Fingerprint: {}", hex::encode(key.fingerprint()));
+            println!("Parent fingerprint: {}", hex::encode(&key.parent_fingerprint));
+            println!("Chain code: {}", hex::encode(&key.chain_code));
+            println!("Extended private key: {}", key.as_base58check());
+            println!("Extended public key: {}", key.to_extended_public_key().as_base58check());
+            println!("Private key: {}", hex::encode(&key.private_key.key));
+            println!("WIF compressed: {}", key.private_key.as_wif_compressed());
+            println!("Public key: {}", hex::encode(&key.public_key().compressed));
+        }
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+/// Formats a child number, marking hardened ones with a `'`.
+fn format_child_number(child_number: u32) -> String {
+    match child_number >= HARDENED {
+        true => format!("{}'", child_number - HARDENED),
+        false => format!("{}", child_number),
+    }
+}
+
+fn log_inspected_xkey(xkey: &str) {
+    let r = match ExtendedPrivateKey::from_base58check(xkey) {
+        Err(Bip32Error::InvalidKeyData) => ExtendedPublicKey::from_base58check(xkey).map(|key| (None, key)),
+        r => r.map(|key| (Some(key.clone()), key.to_extended_public_key())),
+    };
+
+    match r {
+        Ok((private, public)) => {
+            println!("Type: {}", if private.is_some() { "private" } else { "public" });
+            println!("Network: {}", public.network);
+            println!("Depth: {}", public.depth);
+            println!("Parent fingerprint: {}", hex::encode(&public.parent_fingerprint));
+            println!("Child number: {}", format_child_number(public.child_number));
+            println!("Chain code: {}", hex::encode(&public.chain_code));
+            if let Some(private) = private {
+                println!("Private key: {}", hex::encode(&private.private_key.key));
+            }
+            println!("Public key: {}", hex::encode(&public.public_key.compressed));
+            println!("Fingerprint: {}", hex::encode(public.fingerprint()));
+        }
+        Err(error) => eprintln!("Error parsing extended key: {:?}", error),
+    }
+}
+
+/// Returns the `count` indices from `start`, which must all be unhardened, below 2^31.
+fn unhardened_range(start: u32, count: u32) -> Result<std::ops::Range<u32>, String> {
+    match start.checked_add(count) {
+        Some(end) if end <= HARDENED => Ok(start..end),
+        _ => Err(format!("Index range {}+{} goes past the last unhardened index {}", start, count, HARDENED - 1)),
+    }
+}
+
+fn log_derived_addresses(root: Result<ExtendedPrivateKey, String>, purpose: Purpose, account: u32, range: std::ops::Range<u32>) {
+    let root = match root {
+        Ok(root) => root,
+        Err(error) => return eprintln!("{}", error),
+    };
+
+    let account_path = match purpose.account_path(root.network, account) {
+        Ok(path) => path,
+        Err(error) => return eprintln!("Error deriving account: {:?}", error),
+    };
+
+    let account_key = match root.derive_path(&account_path) {
+        Ok(key) => key,
+        Err(error) => return eprintln!("Error deriving key: {:?}", error),
+    };
+
+    println!("{} account {}: {}", purpose, account, account_path);
+    println!("{:<22} {:<62} {:<66} WIF", "Path", "Address", "Public key");
+
+    for change in [0, 1] {
+        for index in range.clone() {
+            let path = account_path.child(change).child(index);
+
+            let r = account_key
+                .derive_child(change)
+                .and_then(|key| key.derive_child(index))
+                .map_err(|error| format!("Error deriving key: {:?}", error))
+                .and_then(|key| {
+                    purpose
+                        .address(key.public_key())
+                        .map(|address| (key, address))
+                        .map_err(|error| format!("Error tweaking public key: {:?}", error))
+                });
+
+            match r {
+                Ok((key, address)) => println!(
+                    "{:<22} {:<62} {:<66} {}",
+                    path.to_string(),
+                    address,
+                    hex::encode(&key.public_key().compressed),
+                    key.private_key.as_wif_compressed(),
+                ),
+                Err(error) => eprintln!("{}: {}", path, error),
+            }
+        }
+    }
+}
+
+fn log_converted_xkey(xkey: &str, to: Option<&str>) {
+    let source = match slip132::key_version_of(xkey) {
+        Ok(source) => source,
+        Err(error) => return eprintln!("Error parsing extended key: {:?}", error),
+    };
+
+    let target = match to {
+        Some(prefix) => match KeyVersion::from_prefix(prefix) {
+            Some(target) => target,
+            None => return eprintln!("Unknown extended key prefix: {}", prefix),
+        },
+        None => source.standard(),
+    };
+
+    match slip132::convert(xkey, target) {
+        Ok(converted) => {
+            println!("Source: {} ({}, {})", source.prefix, source.network, source.script_type);
+            println!("Target: {} ({}, {})", target.prefix, target.network, target.script_type);
+            println!("{}", converted);
+        }
+        Err(error) => eprintln!("Error converting extended key: {:?}", error),
+    }
+}
+
+fn log_descriptor(descriptor: &str, range: std::ops::Range<u32>, network: Option<Network>) {
+    let parsed = match Descriptor::from_str(descriptor) {
+        Ok(parsed) => parsed,
+        Err(error) => return eprintln!("Error parsing descriptor: {:?}", error),
+    };
+
+    let network = network.unwrap_or_else(|| {
+        parsed
+            .keys()
+            .iter()
+            .find_map(|key| match &key.source {
+                KeySource::Xprv(xprv, ..) => Some(xprv.network),
+                KeySource::Xpub(xpub, ..) => Some(xpub.network),
+                KeySource::Single(..) => None,
+            })
+            .unwrap_or_default()
+    });
+
+    println!("Descriptor: {}", parsed.to_string_with_checksum());
+    if descriptor.contains('#') {
+        println!("Checksum: valid");
+    } else {
+        println!("Checksum: added");
+    }
+
+    let range = if parsed.is_ranged() { range } else { 0..1 };
+
+    for index in range {
+        let address = match parsed.address_at(index, network) {
+            Err(DescriptorError::NoAddress) => String::from("-"),
+            other => other.unwrap_or_else(|error| format!("{:?}", error)),
+        };
+
+        match parsed.script_pubkey_at(index) {
+            Ok(script) if parsed.is_ranged() => println!("{}: {} {}", index, address, hex::encode(script)),
+            Ok(script) => println!("{} {}", address, hex::encode(script)),
+            Err(error) => return eprintln!("Error deriving index {}: {:?}", index, error),
+        }
+    }
+}
+
+fn log_derived_address(arg: &HdPathArg, address_type: AddressType, network: Option<Network>) {
+    let r = derive_key(arg, network)
+        .and_then(|key| {
+            key.public_key()
+                .get_address(address_type.into())
+                .map_err(|error| format!("Error getting address: {:?}", error))
+        });
+
+    match r {
+        Ok(address) => println!("{}", address),
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+fn log_taproot_address(arg: &KeyArg, merkle_root: Option<&str>, network: Option<Network>) {
+    let merkle_root = match merkle_root.map(hex::decode).transpose() {
+        Ok(merkle_root) => merkle_root,
+        Err(error) => return eprintln!("Error decoding merkle root: {:?}", error),
+    };
+
+    let k = parse_public_key(arg, network, true);
+
+    match k {
+        Ok(pubkey) => match pubkey.get_p2tr_address(merkle_root.as_deref()) {
+            Ok(address) => println!("{}", address),
+            Err(error) => eprintln!("Error tweaking public key: {:?}", error),
+        },
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+fn log_coordinates(arg: &KeyArg, network: Option<Network>) {
+    let k = parse_public_key(arg, network, false);
 
     match k {
         Ok(pubkey) => {
@@ -98,12 +1078,12 @@ fn log_coordinates(private_key: &str) {
             println!("x = {}", x);
             println!("y = {}", y);
         }
-        Err(error) => eprintln!("Error getting address from private key string: {:?}", error),
+        Err(error) => eprintln!("{}", error),
     }
 }
 
 fn log_hex_compressed_private_key(private_key: &str) {
-    let r = PrivateKey::from_str(private_key);
+    let r = PrivateKey::from_hex_or_wif(private_key);
 
     match r {
         Ok(privkey) => println!("Compressed public key: {}", privkey.as_hex_compressed_string()),
@@ -111,8 +1091,8 @@ fn log_hex_compressed_private_key(private_key: &str) {
     }
 }
 
-fn log_wif_format(private_key: &str) {
-    let r = PrivateKey::from_str(private_key);
+fn log_wif_format(private_key: &str, network: Option<Network>) {
+    let r = parse_private_key(private_key, network);
 
     match r {
         Ok(privkey) => println!("WIF: {}", privkey.as_wif()),
@@ -120,8 +1100,8 @@ fn log_wif_format(private_key: &str) {
     }
 }
 
-fn log_wif_compressed_format(private_key: &str) {
-    let r = PrivateKey::from_str(private_key);
+fn log_wif_compressed_format(private_key: &str, network: Option<Network>) {
+    let r = parse_private_key(private_key, network);
 
     match r {
         Ok(privkey) => println!("WIF compressed: {}", privkey.as_wif_compressed()),
@@ -129,6 +1109,19 @@ fn log_wif_compressed_format(private_key: &str) {
     }
 }
 
+fn log_decoded_wif(wif: &str) {
+    let r = PrivateKey::from_wif(wif);
+
+    match r {
+        Ok(privkey) => {
+            println!("Private key: {}", hex::encode(&privkey.key));
+            println!("Compressed: {}", privkey.compressed == Some(true));
+            println!("Network: {}", privkey.network);
+        }
+        Err(error) => eprintln!("Error decoding WIF: {:?}", error),
+    }
+}
+
 fn log_base58_decoded(encoded: &str) {
     let r = base58decode(encoded);
 
@@ -137,9 +1130,364 @@ fn log_base58_decoded(encoded: &str) {
             println!("Version: {}", decoded.0);
             println!("Payload: {}", decoded.1);
             println!("Checksum: {}", decoded.2);
+
+            match base58check_decode(encoded) {
+                Ok(data) => {
+                    println!("Checksum valid: true");
+
+                    match guess_payload_type(&data) {
+                        Some(payload_type) => println!("Probable type: {}", payload_type),
+                        None => println!("Probable type: unknown"),
+                    }
+                }
+                Err(_) => println!("Checksum valid: false"),
+            }
         },
         Err(error) => {
             eprintln!("Error decoding input: {:?}", error);
         }
     }
 }
+
+fn log_base58_encoded(version: &str, payload: &str) {
+    let r = version
+        .to_string()
+        .to_byte_array()
+        .and_then(|version| Ok((version, payload.to_string().to_byte_array()?)));
+
+    match r {
+        Ok((version, payload)) => println!("{}", base58check_encode(&version, &payload)),
+        Err(error) => eprintln!("Error decoding hex input: {:?}", error),
+    }
+}
+
+fn log_validated_address(address: &str) {
+    let r = Address::from_str(address);
+
+    match r {
+        Ok(parsed) => {
+            println!("Valid: true");
+            println!("Network: {}", parsed.network);
+            println!("Type: {}", parsed.address_type);
+            if let Some(version) = parsed.witness_version {
+                println!("Witness version: {}", version);
+            }
+            println!("Payload: {}", hex::encode(&parsed.payload));
+            println!("scriptPubKey: {}", hex::encode(parsed.script_pubkey()));
+        }
+        Err(error) => {
+            eprintln!("Invalid address: {:?}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn log_signed_message(private_key: &str, message: &str, address_type: AddressType, uncompressed: bool, network: Option<Network>) {
+    let privkey = match parse_private_key(private_key, network) {
+        Ok(privkey) => privkey,
+        Err(error) => return eprintln!("Error parsing private key: {:?}", error),
+    };
+
+    // An uncompressed WIF signs for its uncompressed address
+    let uncompressed = uncompressed || privkey.compressed == Some(false);
+
+    let pubkey = PublicKey::from_private_key(privkey.clone());
+
+    let (signature_type, address) = match (address_type, uncompressed) {
+        (AddressType::Legacy, true) => (SignatureType::P2pkhUncompressed, pubkey.get_address_from_uncompressed()),
+        (AddressType::Legacy, false) => (SignatureType::P2pkh, pubkey.get_address_from_compressed()),
+        (AddressType::Nested, false) => (SignatureType::P2shP2wpkh, pubkey.get_p2sh_p2wpkh_address()),
+        (AddressType::Segwit, false) => (SignatureType::P2wpkh, pubkey.get_p2wpkh_address()),
+        (AddressType::Taproot, _) => return eprintln!("BIP137 signatures don't support taproot addresses"),
+        (_, true) => return eprintln!("Segwit addresses require a compressed public key"),
+    };
+
+    match bip137::sign_message(&privkey, message, signature_type) {
+        Ok(signature) => {
+            println!("Address: {}", address);
+            println!("Signature: {}", signature);
+        }
+        Err(error) => eprintln!("Error signing message: {:?}", error),
+    }
+}
+
+fn log_verified_message(address: &str, message: &str, signature: &str) {
+    match bip137::verify_message(address, message, signature) {
+        Ok(true) => println!("Valid: true"),
+        Ok(false) => {
+            println!("Valid: false");
+            std::process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("Error verifying signature: {:?}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn log_bip322_signed_message(private_key: &str, message: &str, address_type: AddressType, full: bool, network: Option<Network>) {
+    let privkey = match parse_private_key(private_key, network) {
+        Ok(privkey) => privkey,
+        Err(error) => return eprintln!("Error parsing private key: {:?}", error),
+    };
+
+    let address_type = match address_type {
+        AddressType::Segwit => address::AddressType::P2wpkh,
+        AddressType::Taproot => address::AddressType::P2tr,
+        _ => return eprintln!("BIP322 signing supports segwit and taproot addresses"),
+    };
+
+    let format = if full { SignatureFormat::Full } else { SignatureFormat::Simple };
+
+    match bip322::sign(&privkey, message, address_type, format) {
+        Ok((signature, address)) => {
+            println!("Address: {}", address);
+            println!("Signature: {}", signature);
+        }
+        Err(error) => eprintln!("Error signing message: {:?}", error),
+    }
+}
+
+fn log_bip322_verified_message(address: &str, message: &str, signature: &str) {
+    match bip322::verify(address, message, signature) {
+        Ok(true) => println!("Valid: true"),
+        Ok(false) => {
+            println!("Valid: false");
+            std::process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("Error verifying signature: {:?}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// A signature given on the command line, with how it was encoded.
+struct ParsedSignature {
+    signature: Signature,
+    sighash_type: Option<u8>,
+    /// `None` for compact signatures, otherwise the BIP66 strict DER check.
+    strict_der: Option<Result<(), SignatureError>>,
+}
+
+/// Parses a hex compact signature, or a DER one with or without a trailing sighash byte.
+fn parse_signature(signature: &str) -> Result<ParsedSignature, String> {
+    let bytes = hex::decode(signature).map_err(|error| format!("Error parsing signature: {:?}", error))?;
+
+    if bytes.len() == 64 {
+        return Ok(ParsedSignature {
+            signature: Signature::from_compact(&bytes).map_err(|error| format!("Error parsing signature: {:?}", error))?,
+            sighash_type: None,
+            strict_der: None,
+        });
+    }
+
+    let (der, sighash_type) = match bytes.get(1) {
+        Some(len) if *len as usize + 3 == bytes.len() => (&bytes[..bytes.len() - 1], bytes.last().copied()),
+        _ => (&bytes[..], None),
+    };
+
+    let (signature, strict_der) = match Signature::from_der(der) {
+        Ok(signature) => (signature, Ok(())),
+        Err(error) => match Signature::from_der_lax(der) {
+            Ok(signature) => (signature, Err(error)),
+            Err(_) => return Err(format!("Error parsing signature: {:?}", error)),
+        },
+    };
+
+    Ok(ParsedSignature {
+        signature,
+        sighash_type,
+        strict_der: Some(strict_der),
+    })
+}
+
+fn log_signed_hash(private_key: &str, hash: &str, network: Option<Network>) {
+    let r = parse_private_key(private_key, network)
+        .map_err(|error| format!("Error parsing private key: {:?}", error))
+        .and_then(|privkey| {
+            let digest = hex::decode(hash).map_err(|error| format!("Error parsing hash: {:?}", error))?;
+
+            privkey.sign_ecdsa(&digest).map_err(|error| format!("Error signing hash: {:?}", error))
+        });
+
+    match r {
+        Ok(signature) => {
+            println!("DER: {}", hex::encode(signature.to_der()));
+            println!("Compact: {}", hex::encode(signature.to_compact()));
+            println!("r: {}", hex::encode(&signature.r));
+            println!("s: {}", hex::encode(&signature.s));
+        }
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+fn log_verified_signature(public_key: &str, hash: &str, signature: &str) {
+    let r = PublicKey::from_hex(public_key)
+        .map_err(|error| format!("Error parsing public key: {:?}", error))
+        .and_then(|pubkey| {
+            let digest = hex::decode(hash).map_err(|error| format!("Error parsing hash: {:?}", error))?;
+            let parsed = parse_signature(signature)?;
+
+            pubkey
+                .verify_ecdsa(&digest, &parsed.signature)
+                .map_err(|error| format!("Error verifying signature: {:?}", error))
+        });
+
+    log_verification_result(r)
+}
+
+fn log_inspected_signature(signature: &str) {
+    let parsed = match parse_signature(signature) {
+        Ok(parsed) => parsed,
+        Err(error) => return eprintln!("{}", error),
+    };
+
+    match &parsed.strict_der {
+        None => println!("Encoding: compact"),
+        Some(_) => println!("Encoding: DER"),
+    }
+    println!("r: {}", hex::encode(&parsed.signature.r));
+    println!("s: {}", hex::encode(&parsed.signature.s));
+    if let Some(sighash_type) = parsed.sighash_type {
+        println!("Sighash type: 0x{:02x}", sighash_type);
+    }
+    match &parsed.strict_der {
+        None => println!("BIP66 strict DER: n/a"),
+        Some(Ok(())) => println!("BIP66 strict DER: true"),
+        Some(Err(error)) => println!("BIP66 strict DER: false ({:?})", error),
+    }
+
+    if !parsed.signature.is_in_range() {
+        println!("Valid r and s: false (zero or not below the curve order)");
+        println!("Low S: false");
+        return;
+    }
+    println!("Valid r and s: true");
+
+    let low_s = parsed.signature.is_low_s();
+    println!("Low S: {}", low_s);
+    if !low_s {
+        let mut normalized = parsed.signature.clone();
+        normalized.normalize_s();
+        println!("Normalized: {}", hex::encode(normalized.to_der()));
+    }
+}
+
+fn log_schnorr_signature(private_key: &str, message: &str, aux: Option<&str>, network: Option<Network>) {
+    let r = parse_private_key(private_key, network)
+        .map_err(|error| format!("Error parsing private key: {:?}", error))
+        .and_then(|privkey| {
+            let message = hex::decode(message).map_err(|error| format!("Error parsing message: {:?}", error))?;
+            let aux = aux
+                .map(hex::decode)
+                .transpose()
+                .map_err(|error| format!("Error parsing aux: {:?}", error))?;
+
+            let signature = privkey
+                .sign_schnorr(&message, aux.as_deref())
+                .map_err(|error| format!("Error signing message: {:?}", error))?;
+
+            Ok((PublicKey::from_private_key(privkey).x_only(), signature))
+        });
+
+    match r {
+        Ok((public_key, signature)) => {
+            println!("Public key: {}", hex::encode(public_key));
+            println!("Signature: {}", hex::encode(signature));
+        }
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+fn log_verification_result(r: Result<bool, String>) {
+    match r {
+        Ok(true) => println!("Valid: true"),
+        Ok(false) => {
+            println!("Valid: false");
+            std::process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn log_verified_schnorr_signature(public_key: &str, message: &str, signature: &str) {
+    let decoded = [public_key, message, signature]
+        .iter()
+        .map(hex::decode)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| format!("Error parsing hex: {:?}", error));
+
+    log_verification_result(decoded.and_then(|decoded| {
+        schnorr::verify(&decoded[0], &decoded[1], &decoded[2]).map_err(|error| format!("Error verifying signature: {:?}", error))
+    }))
+}
+
+fn log_batch_verified_schnorr_signatures(file: &str) {
+    let contents = match std::fs::read_to_string(file) {
+        Ok(contents) => contents,
+        Err(error) => return eprintln!("Error reading {}: {}", file, error),
+    };
+
+    let mut triples = Vec::new();
+    for (number, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let decoded = line.split_whitespace().map(hex::decode).collect::<Result<Vec<_>, _>>();
+
+        match decoded {
+            Ok(decoded) if decoded.len() == 3 => triples.push(decoded),
+            _ => return eprintln!("Error parsing line {}: expected three hex fields", number + 1),
+        }
+    }
+
+    let items: Vec<(&[u8], &[u8], &[u8])> = triples
+        .iter()
+        .map(|triple| (&triple[0][..], &triple[1][..], &triple[2][..]))
+        .collect();
+
+    println!("Signatures: {}", items.len());
+    log_verification_result(schnorr::verify_batch(&items).map_err(|error| format!("Error verifying signatures: {:?}", error)))
+}
+
+fn log_recovered_public_keys(hash: &str, signature: &str, network: Option<Network>) {
+    let digest = match hex::decode(hash) {
+        Ok(digest) => digest,
+        Err(error) => return eprintln!("Error parsing hash: {:?}", error),
+    };
+
+    let bytes = match hex::decode(signature).or_else(|_| base64::decode(signature)) {
+        Ok(bytes) => bytes,
+        Err(_) => return eprintln!("Error parsing signature: expected hex or base64"),
+    };
+
+    let (compact, header) = match bytes.len() {
+        65 => match SignatureType::from_header(bytes[0]) {
+            Ok((_, recovery_id)) => (&bytes[1..], Some(recovery_id as u8)),
+            Err(error) => return eprintln!("Error parsing signature header: {:?}", error),
+        },
+        _ => (&bytes[..], None),
+    };
+
+    let candidates = Signature::from_compact(compact).and_then(|signature| signature.recover_public_keys(&digest));
+
+    match candidates {
+        Ok(candidates) => {
+            for (recovery_id, mut pubkey) in candidates {
+                pubkey.network = network.unwrap_or_default();
+
+                match header {
+                    Some(id) if id == recovery_id => println!("Recovery id {} (matches header):", recovery_id),
+                    _ => println!("Recovery id {}:", recovery_id),
+                }
+                println!("  Compressed: {}", hex::encode(&pubkey.compressed));
+                println!("  Uncompressed: {}", hex::encode(&pubkey.uncompressed));
+                println!("  P2PKH: {}", pubkey.clone().get_address_from_compressed());
+                println!("  P2PKH (uncompressed): {}", pubkey.clone().get_address_from_uncompressed());
+                println!("  P2WPKH: {}", pubkey.get_p2wpkh_address());
+            }
+        }
+        Err(error) => eprintln!("Error recovering public keys: {:?}", error),
+    }
+}
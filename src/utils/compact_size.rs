@@ -0,0 +1,27 @@
+/// Returns the Bitcoin CompactSize encoding of `n`, the variable length integer prefixing
+/// lengths in messages and transactions.
+pub fn compact_size(n: u64) -> Vec<u8> {
+    match n {
+        0..=0xfc => vec![n as u8],
+        0xfd..=0xffff => [&[0xfd], &(n as u16).to_le_bytes()[..]].concat(),
+        0x10000..=0xffff_ffff => [&[0xfe], &(n as u32).to_le_bytes()[..]].concat(),
+        _ => [&[0xff], &n.to_le_bytes()[..]].concat(),
+    }
+}
+
+#[cfg(test)]
+mod compact_size_tests {
+    use super::compact_size;
+
+    #[test]
+    fn should_return_expected_encoding() {
+        assert_eq!(compact_size(0), vec![0x00]);
+        assert_eq!(compact_size(0xfc), vec![0xfc]);
+        assert_eq!(compact_size(0xfd), vec![0xfd, 0xfd, 0x00]);
+        assert_eq!(compact_size(0x1_0000), vec![0xfe, 0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(
+            compact_size(0x1_0000_0000),
+            vec![0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]
+        );
+    }
+}
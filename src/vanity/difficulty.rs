@@ -0,0 +1,180 @@
+use num::{BigUint, One, ToPrimitive, Zero};
+use std::time::Duration;
+
+use crate::vanity::VanityStats;
+
+/// The base58 alphabet, in digit order
+pub const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// How hard a vanity pattern is to find, as the chance that a single random key matches it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    pub probability: f64,
+}
+
+impl Difficulty {
+    pub fn new(probability: f64) -> Self {
+        Difficulty { probability }
+    }
+
+    /// Returns the mean number of keys to check before a match.
+    pub fn expected_attempts(&self) -> f64 {
+        1.0 / self.probability
+    }
+
+    /// Returns the chance that at least one of `attempts` random keys matches.
+    pub fn probability_within(&self, attempts: u64) -> f64 {
+        -(attempts as f64 * (-self.probability).ln_1p()).exp_m1()
+    }
+
+    /// Returns the number of keys to check to find a match with the chance `probability`.
+    pub fn attempts_for_probability(&self, probability: f64) -> f64 {
+        (-probability).ln_1p() / (-self.probability).ln_1p()
+    }
+
+    /// Returns how much longer a search running at the rate of `stats` needs to reach the
+    /// chance `probability` of a match, or `None` before the rate is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - The attempts and elapsed time of the search so far.
+    /// * `probability` - The chance to reach, e.g. 0.5 for the median.
+    pub fn eta(&self, stats: &VanityStats, probability: f64) -> Option<Duration> {
+        let rate = stats.keys_per_second();
+        if stats.attempts == 0 || !rate.is_normal() {
+            return None;
+        }
+
+        let remaining = (self.attempts_for_probability(probability) - stats.attempts as f64).max(0.0);
+        Duration::try_from_secs_f64(remaining / rate).ok()
+    }
+}
+
+/// Returns the value of a base58 digit.
+pub fn base58_digit(c: char) -> Option<u32> {
+    BASE58_ALPHABET.find(c).map(|index| index as u32)
+}
+
+/// Returns the chance that the base58 encoding of `version` followed by `payload_len` uniformly
+/// random bytes starts with `target`.
+///
+/// Leading zero bytes are encoded as `1`s and the rest as a big endian base58 number, so the
+/// encodings starting with `target` form a few intervals of values, whose share of the possible
+/// values is counted exactly.
+///
+/// # Arguments
+///
+/// * `version` - The version bytes, e.g. `[0x00]` for mainnet P2PKH addresses.
+/// * `payload_len` - The number of random bytes after the version, 24 for a hash and checksum.
+/// * `target` - The wanted start of the encoding.
+pub fn base58_prefix_probability(version: &[u8], payload_len: usize, target: &str) -> f64 {
+    let digits: Option<Vec<u32>> = target.chars().map(base58_digit).collect();
+    let digits = match digits {
+        Some(digits) => digits,
+        None => return 0.0,
+    };
+
+    let len = version.len() + payload_len;
+    let byte = |exp: usize| BigUint::from(256u32).pow(exp as u32);
+    let low = BigUint::from_bytes_be(version) * byte(payload_len);
+    let high = (BigUint::from_bytes_be(version) + BigUint::one()) * byte(payload_len);
+
+    let zeros = digits.iter().take_while(|&&digit| digit == 0).count();
+    let rest = &digits[zeros..];
+
+    if zeros > len {
+        return 0.0;
+    }
+
+    let count = if rest.is_empty() {
+        // At least `zeros` leading zero bytes
+        overlap(&low, &high, &BigUint::zero(), &byte(len - zeros))
+    } else if zeros == len {
+        BigUint::zero()
+    } else {
+        // Exactly `zeros` leading zero bytes, then a number whose digits start with `rest`
+        let start = byte(len - zeros - 1).max(low.clone());
+        let end = byte(len - zeros).min(high.clone());
+        let value = rest.iter().fold(BigUint::zero(), |acc, &digit| acc * 58u32 + digit);
+
+        let mut count = BigUint::zero();
+        let mut scale = BigUint::one();
+        while &value * &scale < end {
+            count += overlap(&start, &end, &(&value * &scale), &((&value + 1u32) * &scale));
+            scale *= 58u32;
+        }
+        count
+    };
+
+    count.to_f64().unwrap_or(0.0) / (high - low).to_f64().unwrap_or(f64::INFINITY)
+}
+
+/// Returns the number of integers in both `[a_start, a_end)` and `[b_start, b_end)`.
+fn overlap(a_start: &BigUint, a_end: &BigUint, b_start: &BigUint, b_end: &BigUint) -> BigUint {
+    let start = a_start.max(b_start);
+    let end = a_end.min(b_end);
+
+    if end > start {
+        end - start
+    } else {
+        BigUint::zero()
+    }
+}
+
+#[cfg(test)]
+mod difficulty_tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual / expected - 1.0).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn should_count_mainnet_p2pkh_first_characters() {
+        // Every mainnet P2PKH address starts with 1, and a second 1 needs a zero byte
+        assert_close(base58_prefix_probability(&[0x00], 24, "1"), 1.0);
+        assert_close(base58_prefix_probability(&[0x00], 24, "11"), 1.0 / 256.0);
+        assert_eq!(base58_prefix_probability(&[0x00], 24, "2"), 0.0);
+    }
+
+    #[test]
+    fn should_count_testnet_p2pkh_first_characters() {
+        let m = base58_prefix_probability(&[0x6f], 24, "m");
+        let n = base58_prefix_probability(&[0x6f], 24, "n");
+
+        assert_close(m + n, 1.0);
+        assert!(m > 0.5);
+        assert_eq!(base58_prefix_probability(&[0x6f], 24, "2"), 0.0);
+        assert_eq!(base58_prefix_probability(&[0x6f], 24, "ma"), 0.0);
+    }
+
+    #[test]
+    fn should_match_uniform_digits_in_the_middle() {
+        let one = base58_prefix_probability(&[0x00], 24, "12");
+        let two = base58_prefix_probability(&[0x00], 24, "12a");
+
+        assert!((two / one - 1.0 / 58.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_reject_characters_outside_the_alphabet() {
+        assert_eq!(base58_prefix_probability(&[0x00], 24, "10"), 0.0);
+        assert_eq!(base58_digit('l'), None);
+        assert_eq!(base58_digit('z'), Some(57));
+    }
+
+    #[test]
+    fn should_estimate_attempts_and_eta() {
+        let difficulty = Difficulty::new(0.001);
+        let stats = VanityStats {
+            attempts: 100,
+            elapsed: Duration::from_secs(1),
+        };
+
+        assert_close(difficulty.expected_attempts(), 1000.0);
+        assert!((difficulty.probability_within(693) - 0.5).abs() < 0.001);
+        assert!((difficulty.attempts_for_probability(0.5) - 692.8).abs() < 0.1);
+        assert_eq!(difficulty.eta(&stats, 0.5).unwrap().as_secs(), 5);
+        assert_eq!(difficulty.eta(&VanityStats { attempts: 0, ..stats }, 0.5), None);
+    }
+}
@@ -0,0 +1,336 @@
+use secp256k1::{rand, Secp256k1, SecretKey};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::address::AddressType;
+use crate::key::{PrivateKey, PublicKey};
+use crate::network::Network;
+
+mod difficulty;
+pub use difficulty::{base58_digit, base58_prefix_probability, Difficulty, BASE58_ALPHABET};
+
+mod pattern;
+pub use pattern::{address_type, max_pattern_length, parse_patterns, patterns_difficulty, validate_prefix, Pattern, PatternKind};
+
+/// Keys each worker checks between two looks at the stop flag and attempt counter.
+const BATCH_SIZE: u64 = 256;
+
+/// How often the thread waiting for the workers looks at the stop flag.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, PartialEq)]
+pub enum VanityError {
+    /// Base58 leaves out 0, O, I and l.
+    InvalidCharacter(char),
+    /// Bech32 leaves out 1, b, i and o, and segwit addresses are lower case.
+    InvalidBech32Character(char),
+    /// `max` counts the characters after the leading one of base58 addresses, and after the
+    /// human readable part, separator and witness version of segwit ones.
+    TooLong { length: usize, max: usize, address_type: AddressType },
+    /// No address of the network has this prefix, `first_characters` are the ones that can
+    /// follow the leading character.
+    Unreachable { prefix: String, first_characters: String },
+    InvalidRegex(String),
+    /// A patterns file line starts with a kind other than prefix, suffix, contains or regex.
+    UnknownKind(String),
+    InvalidLine { line: usize, error: Box<VanityError> },
+    NoPattern,
+    MixedAddressTypes,
+    /// Only P2PKH, P2SH, P2WPKH and P2TR addresses can be searched.
+    UnsupportedType(AddressType),
+    /// None of `samples` random addresses matched the regex, which may never match at all.
+    NoSampledMatch { regex: String, samples: u32 },
+}
+
+/// Throughput of a vanity search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VanityStats {
+    pub attempts: u64,
+    pub elapsed: Duration,
+}
+
+impl VanityStats {
+    /// Returns the number of keys checked per second.
+    pub fn keys_per_second(&self) -> f64 {
+        self.attempts as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// The key pair and address found by a vanity search
+#[derive(Debug, Clone, PartialEq)]
+pub struct VanityResult {
+    pub private_key: PrivateKey,
+    pub public_key: PublicKey,
+    pub address: String,
+    pub stats: VanityStats,
+}
+
+impl VanityResult {
+    /// Writes the private key, as hex and WIF-compressed, to a new file readable only by its
+    /// owner. Fails rather than overwrite an existing file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to create.
+    pub fn write_secret(&self, path: &Path) -> io::Result<()> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(path)?;
+
+        writeln!(file, "Address: {}", self.address)?;
+        writeln!(file, "Private key: {}", self.private_key.as_hex_string())?;
+        writeln!(file, "WIF compressed: {}", self.private_key.as_wif_compressed())
+    }
+}
+
+/// Returns the number of threads to search with by default, one per core.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Searches for a key pair whose address of the type of `patterns` matches any of them, after
+/// checking that prefixes are reachable. Addresses are derived from the compressed public key,
+/// P2SH ones nest a P2WPKH script and P2TR ones commit to no script.
+///
+/// Every worker starts from a random secret key and walks consecutive keys, adding the
+/// generator to the public key instead of multiplying it again. Workers share one context and
+/// all stop as soon as one of them finds a match.
+///
+/// # Arguments
+///
+/// * `patterns` - The alternative patterns wanted in the address.
+/// * `network` - The network the addresses are encoded for.
+/// * `threads` - The number of workers, at least one.
+pub fn search(patterns: &[Pattern], network: Network, threads: usize) -> Result<VanityResult, VanityError> {
+    search_with_progress(patterns, network, threads, Duration::MAX, |_| {})
+}
+
+/// Searches like [`search`], calling `progress` with the attempts so far every `interval`.
+///
+/// # Arguments
+///
+/// * `patterns` - The alternative patterns wanted in the address.
+/// * `network` - The network the addresses are encoded for.
+/// * `threads` - The number of workers, at least one.
+/// * `interval` - The time between two calls to `progress`.
+/// * `progress` - Called on the calling thread while the workers search.
+pub fn search_with_progress<F>(
+    patterns: &[Pattern],
+    network: Network,
+    threads: usize,
+    interval: Duration,
+    mut progress: F,
+) -> Result<VanityResult, VanityError>
+where
+    F: FnMut(&VanityStats),
+{
+    let address_type = address_type(patterns)?;
+
+    // Suffixes and contained text can always match, while an unreachable prefix or a regex no
+    // sampled address matches could keep the search going forever
+    for pattern in patterns.iter().filter(|pattern| matches!(pattern.kind, PatternKind::Prefix | PatternKind::Regex)) {
+        pattern.difficulty(network)?;
+    }
+
+    let secp = Secp256k1::new();
+    let stop = AtomicBool::new(false);
+    let attempts = AtomicU64::new(0);
+    let found: Mutex<Option<(SecretKey, secp256k1::PublicKey)>> = Mutex::new(None);
+    let start = Instant::now();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                let generator = secp256k1::PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&one()).unwrap());
+                let (mut secret, mut pubkey) = random_key_pair(&secp);
+
+                while !stop.load(Ordering::Relaxed) {
+                    for _ in 0..BATCH_SIZE {
+                        let address = PublicKey::from_secp256k1(&pubkey, network).get_address(address_type);
+
+                        if address.is_ok_and(|address| patterns.iter().any(|pattern| pattern.is_match(&address))) {
+                            if !stop.swap(true, Ordering::Relaxed) {
+                                *found.lock().unwrap() = Some((secret, pubkey));
+                            }
+                            break;
+                        }
+
+                        // Moves to the next key pair, (k + 1, P + G)
+                        match (secret.add_assign(&one()), pubkey.combine(&generator)) {
+                            (Ok(()), Ok(next)) => pubkey = next,
+                            // Only reached if the walk wraps around the curve order
+                            _ => (secret, pubkey) = random_key_pair(&secp),
+                        }
+                    }
+
+                    attempts.fetch_add(BATCH_SIZE, Ordering::Relaxed);
+                }
+            });
+        }
+
+        let mut last_progress = Instant::now();
+        while !stop.load(Ordering::Relaxed) {
+            thread::sleep(POLL_INTERVAL);
+
+            if last_progress.elapsed() >= interval {
+                progress(&VanityStats {
+                    attempts: attempts.load(Ordering::Relaxed),
+                    elapsed: start.elapsed(),
+                });
+                last_progress = Instant::now();
+            }
+        }
+    });
+
+    let (secret, pubkey) = found.into_inner().unwrap().expect("workers only stop on a match");
+    let public_key = PublicKey::from_secp256k1(&pubkey, network);
+
+    Ok(VanityResult {
+        private_key: PrivateKey {
+            key: secret.secret_bytes().to_vec(),
+            compressed: Some(true),
+            network,
+        },
+        address: public_key.clone().get_address(address_type).expect("the worker derived it"),
+        public_key,
+        stats: VanityStats {
+            attempts: attempts.into_inner(),
+            elapsed: start.elapsed(),
+        },
+    })
+}
+
+/// Returns a random secret key and its public key.
+fn random_key_pair<C: secp256k1::Signing>(secp: &Secp256k1<C>) -> (SecretKey, secp256k1::PublicKey) {
+    let secret = SecretKey::new(&mut rand::thread_rng());
+
+    (secret, secp256k1::PublicKey::from_secret_key(secp, &secret))
+}
+
+/// Returns the scalar 1 as 32 big endian bytes.
+fn one() -> [u8; 32] {
+    let mut one = [0; 32];
+    one[31] = 1;
+    one
+}
+
+#[cfg(test)]
+mod vanity_tests {
+    use super::*;
+
+    #[test]
+    fn should_return_address_with_prefix() {
+        let result = search(&[Pattern::prefix("a").unwrap()], Network::Mainnet, 2).unwrap();
+
+        assert!(result.address.starts_with("1a"));
+        assert!(result.stats.attempts > 0);
+    }
+
+    #[test]
+    fn should_return_matching_key_pair() {
+        let result = search(&[Pattern::prefix("b").unwrap()], Network::Mainnet, 2).unwrap();
+        let public_key = PublicKey::from_private_key(result.private_key.clone());
+
+        assert_eq!(public_key.compressed, result.public_key.compressed);
+        assert_eq!(public_key.get_address_from_compressed(), result.address);
+        assert_eq!(
+            PrivateKey::from_wif(&result.private_key.as_wif_compressed()).unwrap().key,
+            result.private_key.key
+        );
+    }
+
+    #[test]
+    fn should_report_progress() {
+        let mut reports = Vec::new();
+        let result = search_with_progress(&[Pattern::prefix("a").unwrap()], Network::Mainnet, 1, Duration::ZERO, |stats| {
+            reports.push(*stats)
+        })
+        .unwrap();
+
+        assert!(result.address.starts_with("1a"));
+        assert!(reports.windows(2).all(|pair| pair[0].attempts <= pair[1].attempts));
+        assert!(reports.iter().all(|stats| stats.attempts <= result.stats.attempts));
+    }
+
+    #[test]
+    fn should_return_address_matching_any_pattern() {
+        let patterns = parse_patterns("suffix:z\nsuffix:y\ncontains:xx", false, AddressType::P2pkh).unwrap();
+        let result = search(&patterns, Network::Mainnet, 2).unwrap();
+
+        assert!(result.address.ends_with('z') || result.address.ends_with('y') || result.address[1..].contains("xx"));
+        assert_eq!(search(&[], Network::Mainnet, 1), Err(VanityError::NoPattern));
+    }
+
+    #[test]
+    fn should_return_segwit_and_taproot_addresses() {
+        let segwit = Pattern::new(PatternKind::Prefix, "x", false, AddressType::P2wpkh).unwrap();
+        let taproot = Pattern::new(PatternKind::Suffix, "7", false, AddressType::P2tr).unwrap();
+
+        let result = search(&[segwit], Network::Mainnet, 2).unwrap();
+        assert!(result.address.starts_with("bc1qx"));
+        assert_eq!(PublicKey::from_private_key(result.private_key).get_p2wpkh_address(), result.address);
+
+        let result = search(&[taproot], Network::Signet, 2).unwrap();
+        assert!(result.address.starts_with("tb1p") && result.address.ends_with('7'));
+        assert_eq!(result.public_key.get_p2tr_address(None).unwrap(), result.address);
+    }
+
+    #[test]
+    fn should_reject_mixed_address_types() {
+        let patterns = [
+            Pattern::prefix("a").unwrap(),
+            Pattern::new(PatternKind::Prefix, "a", false, AddressType::P2tr).unwrap(),
+        ];
+
+        assert_eq!(search(&patterns, Network::Mainnet, 1), Err(VanityError::MixedAddressTypes));
+    }
+
+    #[test]
+    fn should_refuse_regex_without_sampled_match() {
+        let never = Pattern::new(PatternKind::Regex, "^2", false, AddressType::P2pkh).unwrap();
+
+        assert!(matches!(search(&[never], Network::Mainnet, 1), Err(VanityError::NoSampledMatch { .. })));
+    }
+
+    #[test]
+    fn should_write_secret_to_new_file() {
+        let result = search(&[Pattern::prefix("c").unwrap()], Network::Mainnet, 1).unwrap();
+        let path = std::env::temp_dir().join(format!("btcli-vanity-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        result.write_secret(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+
+        assert!(contents.contains(&result.private_key.as_wif_compressed()));
+        assert!(result.write_secret(&path).is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn should_search_on_testnet() {
+        let result = search(&[Pattern::prefix("x").unwrap()], Network::Testnet, 1).unwrap();
+
+        assert_eq!(&result.address[1..2], "x");
+        assert!(result.address.starts_with('m') || result.address.starts_with('n'));
+    }
+}
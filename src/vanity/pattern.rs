@@ -0,0 +1,532 @@
+use regex::{Regex, RegexBuilder};
+use secp256k1::rand::{self, RngCore};
+use std::fmt;
+
+use crate::address::AddressType;
+use crate::base58encoder::base58check_encode;
+use crate::bech32;
+use crate::network::Network;
+use crate::vanity::difficulty::{base58_prefix_probability, Difficulty, BASE58_ALPHABET};
+use crate::vanity::VanityError;
+
+/// Number of random addresses matched against a regex to estimate its difficulty.
+const REGEX_SAMPLES: u32 = 20_000;
+
+/// Where a pattern has to appear in an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternKind {
+    /// Right after the characters fixed by the network and address type, the leading `1`, `3`,
+    /// `m`, `n` or `2` of base58 addresses and the `bc1q` or `bc1p` of segwit ones.
+    Prefix,
+    Suffix,
+    /// Anywhere after the fixed characters.
+    Contains,
+    /// A regular expression matched against the whole address.
+    Regex,
+}
+
+impl PatternKind {
+    fn name(self) -> &'static str {
+        match self {
+            PatternKind::Prefix => "prefix",
+            PatternKind::Suffix => "suffix",
+            PatternKind::Contains => "contains",
+            PatternKind::Regex => "regex",
+        }
+    }
+}
+
+/// A pattern wanted in a vanity address
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub text: String,
+    pub ignore_case: bool,
+    /// The type of the addresses searched, which sets the characters they are made of.
+    pub address_type: AddressType,
+    regex: Option<Regex>,
+}
+
+impl Pattern {
+    /// Returns a pattern, checking that its characters can appear in an address of
+    /// `address_type`: base58 leaves out 0, O, I and l, and bech32 1, b, i, o and upper case.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Where the pattern has to appear.
+    /// * `text` - The wanted characters, or a regular expression.
+    /// * `ignore_case` - Whether upper and lower case letters match each other.
+    /// * `address_type` - P2PKH, P2SH, P2WPKH or P2TR.
+    pub fn new(kind: PatternKind, text: &str, ignore_case: bool, address_type: AddressType) -> Result<Self, VanityError> {
+        let alphabet = alphabet(address_type)?;
+        let regex = match kind {
+            PatternKind::Regex => Some(
+                RegexBuilder::new(text)
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .map_err(|err| VanityError::InvalidRegex(err.to_string()))?,
+            ),
+            _ => {
+                if let Some(c) = text.chars().find(|&c| case_variants(c, ignore_case, alphabet).is_empty()) {
+                    return Err(match address_type {
+                        AddressType::P2wpkh | AddressType::P2tr => VanityError::InvalidBech32Character(c),
+                        _ => VanityError::InvalidCharacter(c),
+                    });
+                }
+
+                let length = text.chars().count();
+                let max = max_pattern_length(address_type);
+                if length > max {
+                    return Err(VanityError::TooLong { length, max, address_type });
+                }
+
+                None
+            }
+        };
+
+        Ok(Pattern {
+            kind,
+            text: text.to_string(),
+            ignore_case,
+            address_type,
+            regex,
+        })
+    }
+
+    /// Returns a case sensitive prefix pattern for P2PKH addresses.
+    pub fn prefix(text: &str) -> Result<Self, VanityError> {
+        Pattern::new(PatternKind::Prefix, text, false, AddressType::P2pkh)
+    }
+
+    /// Returns the pattern of a line of a patterns file, `prefix:`, `suffix:`, `contains:` or
+    /// `regex:` followed by the pattern. Lines without a kind are prefixes.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line, without its line break.
+    /// * `ignore_case` - Whether upper and lower case letters match each other.
+    /// * `address_type` - P2PKH, P2SH, P2WPKH or P2TR.
+    pub fn parse(line: &str, ignore_case: bool, address_type: AddressType) -> Result<Self, VanityError> {
+        let (kind, text) = match line.split_once(':') {
+            Some(("prefix", text)) => (PatternKind::Prefix, text),
+            Some(("suffix", text)) => (PatternKind::Suffix, text),
+            Some(("contains", text)) => (PatternKind::Contains, text),
+            Some(("regex", text)) => (PatternKind::Regex, text),
+            Some((kind, _)) => return Err(VanityError::UnknownKind(kind.to_string())),
+            None => (PatternKind::Prefix, line),
+        };
+
+        Pattern::new(kind, text, ignore_case, address_type)
+    }
+
+    /// Returns whether `address` matches the pattern.
+    pub fn is_match(&self, address: &str) -> bool {
+        let fixed = match self.address_type {
+            // The human readable part, the separator and the witness version
+            AddressType::P2wpkh | AddressType::P2tr => address.rfind('1').map_or(0, |separator| separator + 2),
+            _ => 1,
+        };
+        let (address, text) = (address.as_bytes(), self.text.as_bytes());
+        let eq = |a: &[u8], b: &[u8]| match self.ignore_case {
+            true => a.eq_ignore_ascii_case(b),
+            false => a == b,
+        };
+
+        match (self.kind, &self.regex) {
+            (PatternKind::Regex, Some(regex)) => std::str::from_utf8(address).is_ok_and(|a| regex.is_match(a)),
+            (PatternKind::Prefix, _) => address.len() >= fixed + text.len() && eq(&address[fixed..fixed + text.len()], text),
+            (PatternKind::Suffix, _) => address.len() >= text.len() && eq(&address[address.len() - text.len()..], text),
+            (PatternKind::Contains, _) => text.is_empty() || address.get(fixed..).is_some_and(|a| a.windows(text.len()).any(|w| eq(w, text))),
+            (PatternKind::Regex, None) => false,
+        }
+    }
+
+    /// Returns how hard finding an address of `network` matching the pattern is.
+    ///
+    /// Base58 prefixes are counted exactly over the values an address can encode, other
+    /// patterns treat the characters they cover as uniform, and regexes are matched against
+    /// random addresses.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - The network the addresses are encoded for.
+    pub fn difficulty(&self, network: Network) -> Result<Difficulty, VanityError> {
+        let alphabet = alphabet(self.address_type)?;
+        let chars: Vec<Vec<char>> = self.text.chars().map(|c| case_variants(c, self.ignore_case, alphabet)).collect();
+        let uniform = |chars: &[Vec<char>]| {
+            chars
+                .iter()
+                .map(|variants| variants.len() as f64 / alphabet.len() as f64)
+                .product::<f64>()
+        };
+
+        let probability = match (self.kind, base58_version(self.address_type, network)) {
+            (PatternKind::Prefix, Some(version)) => prefix_difficulty(&chars, version)?.probability,
+            (PatternKind::Prefix, None) | (PatternKind::Suffix, _) => uniform(&chars),
+            (PatternKind::Contains, _) => {
+                let positions = (max_pattern_length(self.address_type) + 1).saturating_sub(chars.len()) as f64;
+                -(positions * (-uniform(&chars)).ln_1p()).exp_m1()
+            }
+            (PatternKind::Regex, _) => {
+                let matches = (0..REGEX_SAMPLES)
+                    .filter(|_| self.is_match(&random_address(self.address_type, network)))
+                    .count();
+
+                if matches == 0 {
+                    return Err(VanityError::NoSampledMatch {
+                        regex: self.text.clone(),
+                        samples: REGEX_SAMPLES,
+                    });
+                }
+                matches as f64 / REGEX_SAMPLES as f64
+            }
+        };
+
+        Ok(Difficulty::new(probability))
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.kind.name(), self.text)
+    }
+}
+
+/// Returns patterns read from a file with one pattern per line, as parsed by [`Pattern::parse`].
+/// Blank lines and lines starting with `#` are skipped.
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the file.
+/// * `ignore_case` - Whether upper and lower case letters match each other.
+/// * `address_type` - P2PKH, P2SH, P2WPKH or P2TR.
+pub fn parse_patterns(contents: &str, ignore_case: bool, address_type: AddressType) -> Result<Vec<Pattern>, VanityError> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, text)| {
+            Pattern::parse(text, ignore_case, address_type).map_err(|error| VanityError::InvalidLine {
+                line,
+                error: Box::new(error),
+            })
+        })
+        .collect()
+}
+
+/// Returns how hard finding an address matching any of `patterns` is, taking their matches as
+/// independent. The patterns must all be for the same type of address.
+///
+/// # Arguments
+///
+/// * `patterns` - The alternative patterns.
+/// * `network` - The network the addresses are encoded for.
+pub fn patterns_difficulty(patterns: &[Pattern], network: Network) -> Result<Difficulty, VanityError> {
+    address_type(patterns)?;
+
+    let mut miss = 1.0;
+    for pattern in patterns {
+        miss *= 1.0 - pattern.difficulty(network)?.probability;
+    }
+
+    Ok(Difficulty::new(1.0 - miss))
+}
+
+/// Returns the address type shared by `patterns`.
+pub fn address_type(patterns: &[Pattern]) -> Result<AddressType, VanityError> {
+    let first = patterns.first().ok_or(VanityError::NoPattern)?.address_type;
+
+    match patterns.iter().all(|pattern| pattern.address_type == first) {
+        true => Ok(first),
+        false => Err(VanityError::MixedAddressTypes),
+    }
+}
+
+/// Returns the number of characters after the fixed ones of the addresses of `address_type`, and
+/// so the length of the longest pattern.
+pub fn max_pattern_length(address_type: AddressType) -> usize {
+    match address_type {
+        // 20 and 32 bytes of witness program, 5 bits per character, and a 6 character checksum
+        AddressType::P2wpkh => 38,
+        AddressType::P2tr => 58,
+        // 25 bytes, 34 characters for all but the rare values below 58^33
+        _ => 33,
+    }
+}
+
+/// Checks that P2PKH addresses of `network` can have `prefix` after their leading character and
+/// returns how hard it is to find.
+///
+/// # Arguments
+///
+/// * `prefix` - The characters wanted after the leading `1` (or `m`/`n` on test networks).
+/// * `network` - The network the addresses are encoded for.
+pub fn validate_prefix(prefix: &str, network: Network) -> Result<Difficulty, VanityError> {
+    let pattern = Pattern::prefix(prefix)?;
+
+    pattern.difficulty(network)
+}
+
+/// Returns the characters the addresses of `address_type` are made of.
+fn alphabet(address_type: AddressType) -> Result<&'static [u8], VanityError> {
+    match address_type {
+        AddressType::P2pkh | AddressType::P2sh => Ok(BASE58_ALPHABET.as_bytes()),
+        AddressType::P2wpkh | AddressType::P2tr => Ok(bech32::CHARSET),
+        _ => Err(VanityError::UnsupportedType(address_type)),
+    }
+}
+
+/// Returns the version byte of base58 addresses, or `None` for segwit ones.
+fn base58_version(address_type: AddressType, network: Network) -> Option<u8> {
+    match address_type {
+        AddressType::P2pkh => Some(network.p2pkh_prefix()),
+        AddressType::P2sh => Some(network.p2sh_prefix()),
+        _ => None,
+    }
+}
+
+/// Returns an address of `address_type` paying to random bytes.
+fn random_address(address_type: AddressType, network: Network) -> String {
+    let mut program = [0; 32];
+    rand::thread_rng().fill_bytes(&mut program);
+
+    match (address_type, base58_version(address_type, network)) {
+        (_, Some(version)) => base58check_encode(&[version], &program[..20]),
+        (AddressType::P2tr, None) => bech32::encode_segwit_address(network.bech32_hrp(), 1, &program),
+        _ => bech32::encode_segwit_address(network.bech32_hrp(), 0, &program[..20]),
+    }
+}
+
+/// Returns the difficulty of a base58 prefix given the accepted variants of each of its characters.
+///
+/// Only the first characters of an address are unevenly spread, so the variants of the first
+/// character are counted exactly and the others multiply the chance of one spelling.
+fn prefix_difficulty(chars: &[Vec<char>], version: u8) -> Result<Difficulty, VanityError> {
+    let spelling: String = chars.iter().skip(1).map(|variants| variants[0]).collect();
+    let others = chars.iter().skip(1).map(|variants| variants.len() as f64).product::<f64>();
+
+    let probability = match chars.first() {
+        Some(variants) => {
+            variants
+                .iter()
+                .map(|first| prefix_probability(&format!("{}{}", first, spelling), version))
+                .sum::<f64>()
+                * others
+        }
+        None => 1.0,
+    };
+
+    if probability == 0.0 {
+        return Err(VanityError::Unreachable {
+            prefix: chars.iter().map(|variants| variants[0]).collect(),
+            first_characters: BASE58_ALPHABET
+                .chars()
+                .filter(|c| prefix_probability(&c.to_string(), version) > 0.0)
+                .collect(),
+        });
+    }
+
+    Ok(Difficulty::new(probability))
+}
+
+/// Returns the chance that the base58 address of `version` and a random hash has `prefix` after
+/// its leading character.
+fn prefix_probability(prefix: &str, version: u8) -> f64 {
+    BASE58_ALPHABET
+        .chars()
+        .map(|first| {
+            let target = format!("{}{}", first, prefix);
+            base58_prefix_probability(&[version], 24, &target)
+        })
+        .sum()
+}
+
+/// Returns the characters of `alphabet` that `c` stands for, both of its cases if
+/// `ignore_case` is set.
+fn case_variants(c: char, ignore_case: bool, alphabet: &[u8]) -> Vec<char> {
+    let mut variants = vec![c];
+    if ignore_case {
+        variants = vec![c.to_ascii_lowercase(), c.to_ascii_uppercase()];
+        variants.dedup();
+    }
+
+    variants.retain(|&c| c.is_ascii() && alphabet.contains(&(c as u8)));
+    variants
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use super::*;
+
+    #[test]
+    fn should_match_each_kind() {
+        let address = "1BoatSLRHtKNngkdXEeobR76b53LETtpyT";
+
+        assert!(Pattern::prefix("Boat").unwrap().is_match(address));
+        assert!(!Pattern::prefix("boat").unwrap().is_match(address));
+        assert!(Pattern::new(PatternKind::Prefix, "boat", true, AddressType::P2pkh).unwrap().is_match(address));
+        assert!(Pattern::new(PatternKind::Suffix, "TtpyT", false, AddressType::P2pkh).unwrap().is_match(address));
+        assert!(Pattern::new(PatternKind::Contains, "ngkd", false, AddressType::P2pkh).unwrap().is_match(address));
+        assert!(!Pattern::new(PatternKind::Contains, "1Boat", false, AddressType::P2pkh).unwrap().is_match(address));
+        assert!(Pattern::new(PatternKind::Regex, "^1Bo.t", false, AddressType::P2pkh).unwrap().is_match(address));
+        assert!(Pattern::new(PatternKind::Regex, "^1bOAT", true, AddressType::P2pkh).unwrap().is_match(address));
+    }
+
+    #[test]
+    fn should_validate_characters() {
+        assert_eq!(Pattern::prefix("B0b").unwrap_err(), VanityError::InvalidCharacter('0'));
+        assert_eq!(Pattern::prefix("Il").unwrap_err(), VanityError::InvalidCharacter('I'));
+        // l and I are only valid in their other case
+        assert!(Pattern::new(PatternKind::Suffix, "lI", true, AddressType::P2pkh).is_ok());
+        assert_eq!(
+            Pattern::new(PatternKind::Contains, "0", true, AddressType::P2pkh).unwrap_err(),
+            VanityError::InvalidCharacter('0')
+        );
+        assert!(matches!(
+            Pattern::new(PatternKind::Regex, "(", false, AddressType::P2pkh),
+            Err(VanityError::InvalidRegex(_))
+        ));
+        assert_eq!(
+            Pattern::prefix(&"a".repeat(34)).unwrap_err(),
+            VanityError::TooLong {
+                length: 34,
+                max: 33,
+                address_type: AddressType::P2pkh
+            }
+        );
+    }
+
+    #[test]
+    fn should_match_after_segwit_version() {
+        let segwit = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        let taproot = "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c";
+
+        assert!(Pattern::new(PatternKind::Prefix, "ar0s", false, AddressType::P2wpkh).unwrap().is_match(segwit));
+        assert!(Pattern::new(PatternKind::Prefix, "AR0S", true, AddressType::P2wpkh).unwrap().is_match(segwit));
+        assert!(Pattern::new(PatternKind::Contains, "5mdq", false, AddressType::P2wpkh).unwrap().is_match(segwit));
+        assert!(Pattern::new(PatternKind::Prefix, "qqqqp", false, AddressType::P2tr).unwrap().is_match(taproot));
+        assert!(Pattern::new(PatternKind::Suffix, "hn0c", false, AddressType::P2tr).unwrap().is_match(taproot));
+    }
+
+    #[test]
+    fn should_validate_bech32_characters() {
+        for c in ["1", "b", "i", "o", "A"] {
+            assert_eq!(
+                Pattern::new(PatternKind::Prefix, c, false, AddressType::P2wpkh).unwrap_err(),
+                VanityError::InvalidBech32Character(c.chars().next().unwrap())
+            );
+        }
+
+        assert!(Pattern::new(PatternKind::Prefix, "A", true, AddressType::P2tr).is_ok());
+        assert!(Pattern::new(PatternKind::Suffix, &"q".repeat(58), false, AddressType::P2tr).is_ok());
+        assert_eq!(
+            Pattern::new(PatternKind::Suffix, &"q".repeat(39), false, AddressType::P2wpkh).unwrap_err(),
+            VanityError::TooLong {
+                length: 39,
+                max: 38,
+                address_type: AddressType::P2wpkh
+            }
+        );
+        assert_eq!(
+            Pattern::new(PatternKind::Prefix, "q", false, AddressType::P2wsh).unwrap_err(),
+            VanityError::UnsupportedType(AddressType::P2wsh)
+        );
+    }
+
+    #[test]
+    fn should_estimate_bech32_difficulty() {
+        let prefix = Pattern::new(PatternKind::Prefix, "qqq", false, AddressType::P2wpkh).unwrap();
+        let regex = Pattern::new(PatternKind::Regex, "^bc1p[qpzry9x8]", false, AddressType::P2tr).unwrap();
+
+        assert_eq!(prefix.difficulty(Network::Mainnet).unwrap().expected_attempts(), 32768.0);
+        assert!((regex.difficulty(Network::Mainnet).unwrap().probability - 0.25).abs() < 0.02);
+    }
+
+    #[test]
+    fn should_estimate_p2sh_prefix_difficulty() {
+        let pattern = Pattern::new(PatternKind::Prefix, "J", false, AddressType::P2sh).unwrap();
+        let testnet = Pattern::new(PatternKind::Prefix, "z", false, AddressType::P2sh).unwrap();
+
+        assert!(pattern.difficulty(Network::Mainnet).unwrap().expected_attempts() < 58.0);
+        assert!(testnet.difficulty(Network::Testnet).is_err());
+    }
+
+    #[test]
+    fn should_reject_unreachable_prefixes() {
+        assert!(matches!(
+            validate_prefix("a", Network::Testnet),
+            Err(VanityError::Unreachable { first_characters, .. }) if !first_characters.contains('a')
+        ));
+        // Neither case of a can follow m or n
+        assert!(Pattern::new(PatternKind::Prefix, "a", true, AddressType::P2pkh).unwrap().difficulty(Network::Testnet).is_err());
+    }
+
+    #[test]
+    fn should_estimate_prefix_difficulty() {
+        let a = validate_prefix("a", Network::Mainnet).unwrap();
+        let ab = validate_prefix("ab", Network::Mainnet).unwrap();
+
+        assert!((ab.expected_attempts() / a.expected_attempts() - 58.0).abs() < 1e-6);
+        // Zero bytes are rarer than any other leading digit
+        assert!((validate_prefix("1", Network::Mainnet).unwrap().expected_attempts() - 256.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_estimate_ignored_case_and_suffix_difficulty() {
+        let exact = validate_prefix("Bab", Network::Mainnet).unwrap();
+        let ignored = Pattern::new(PatternKind::Prefix, "bab", true, AddressType::P2pkh).unwrap().difficulty(Network::Mainnet).unwrap();
+        let suffix = Pattern::new(PatternKind::Suffix, "ab", false, AddressType::P2pkh).unwrap().difficulty(Network::Mainnet).unwrap();
+
+        // B is a far more common first character than b, each of the others doubles the chance
+        assert!(ignored.probability > 4.0 * exact.probability && ignored.probability < 4.2 * exact.probability);
+        assert!((suffix.expected_attempts() - 58.0 * 58.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_estimate_regex_difficulty_by_sampling() {
+        let pattern = Pattern::new(PatternKind::Regex, "[a-k]$", false, AddressType::P2pkh).unwrap();
+        let difficulty = pattern.difficulty(Network::Mainnet).unwrap();
+
+        // 11 base58 characters, l is left out, and a standard deviation of about 0.003
+        assert!((difficulty.probability - 11.0 / 58.0).abs() < 0.02);
+
+        let never = Pattern::new(PatternKind::Regex, "^2", false, AddressType::P2pkh).unwrap();
+        assert_eq!(
+            never.difficulty(Network::Mainnet),
+            Err(VanityError::NoSampledMatch {
+                regex: "^2".to_string(),
+                samples: REGEX_SAMPLES
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_patterns_file() {
+        let contents = "# Brand\nBoat\n\nsuffix:xyz\ncontains:Moon\nregex:^1[AB]\n";
+        let patterns = parse_patterns(contents, false, AddressType::P2pkh).unwrap();
+
+        assert_eq!(
+            patterns.iter().map(|pattern| pattern.to_string()).collect::<Vec<_>>(),
+            vec!["prefix:Boat", "suffix:xyz", "contains:Moon", "regex:^1[AB]"]
+        );
+        assert_eq!(
+            parse_patterns("Boat\nend:xyz", false, AddressType::P2pkh).unwrap_err(),
+            VanityError::InvalidLine {
+                line: 2,
+                error: Box::new(VanityError::UnknownKind("end".to_string()))
+            }
+        );
+    }
+
+    #[test]
+    fn should_combine_alternatives() {
+        let patterns = parse_patterns("a\nb", false, AddressType::P2pkh).unwrap();
+        let one = validate_prefix("a", Network::Mainnet).unwrap().probability;
+
+        let both = patterns_difficulty(&patterns, Network::Mainnet).unwrap();
+
+        assert!((both.probability - (1.0 - (1.0 - one) * (1.0 - one))).abs() < 1e-12);
+        assert_eq!(patterns_difficulty(&[], Network::Mainnet), Err(VanityError::NoPattern));
+    }
+}